@@ -0,0 +1,219 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// A crafting condition, which changes the effects of the next action. Each condition's
+/// discriminant is its bit in RecipeLevelTable's `ConditionsFlag`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Condition {
+    Normal = 0,
+    Good = 1,
+    Excellent = 2,
+    Poor = 3,
+    Centered = 4,
+    Sturdy = 5,
+    Pliant = 6,
+    Malleable = 7,
+    Primed = 8,
+    #[serde(rename = "Good Omen")]
+    GoodOmen = 9,
+}
+
+/// How a condition modifies the action used during it. Multipliers are percentages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConditionEffect {
+    pub progress_multiplier: u32,
+    pub quality_multiplier: u32,
+    pub durability_cost_multiplier: u32,
+    pub cp_cost_multiplier: u32,
+    /// Added to the action's success rate, in percentage points
+    pub success_rate_bonus: u32,
+    /// Added to the duration of any buff the action applies, in steps
+    pub buff_duration_bonus: u32,
+}
+
+impl Default for ConditionEffect {
+    fn default() -> Self {
+        ConditionEffect {
+            progress_multiplier: 100,
+            quality_multiplier: 100,
+            durability_cost_multiplier: 100,
+            cp_cost_multiplier: 100,
+            success_rate_bonus: 0,
+            buff_duration_bonus: 0,
+        }
+    }
+}
+
+impl Condition {
+    pub const ALL: [Condition; 10] = [
+        Condition::Normal,
+        Condition::Good,
+        Condition::Excellent,
+        Condition::Poor,
+        Condition::Centered,
+        Condition::Sturdy,
+        Condition::Pliant,
+        Condition::Malleable,
+        Condition::Primed,
+        Condition::GoodOmen,
+    ];
+
+    pub fn bit(self) -> u32 {
+        1 << self as u32
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Condition::Normal => "Normal",
+            Condition::Good => "Good",
+            Condition::Excellent => "Excellent",
+            Condition::Poor => "Poor",
+            Condition::Centered => "Centered",
+            Condition::Sturdy => "Sturdy",
+            Condition::Pliant => "Pliant",
+            Condition::Malleable => "Malleable",
+            Condition::Primed => "Primed",
+            Condition::GoodOmen => "Good Omen",
+        }
+    }
+
+    pub fn effect(self) -> ConditionEffect {
+        let normal = ConditionEffect::default();
+        match self {
+            Condition::Normal | Condition::GoodOmen => normal,
+            Condition::Good => ConditionEffect {
+                quality_multiplier: 150,
+                ..normal
+            },
+            Condition::Excellent => ConditionEffect {
+                quality_multiplier: 400,
+                ..normal
+            },
+            Condition::Poor => ConditionEffect {
+                quality_multiplier: 50,
+                ..normal
+            },
+            Condition::Centered => ConditionEffect {
+                success_rate_bonus: 25,
+                ..normal
+            },
+            Condition::Sturdy => ConditionEffect {
+                durability_cost_multiplier: 50,
+                ..normal
+            },
+            Condition::Pliant => ConditionEffect {
+                cp_cost_multiplier: 50,
+                ..normal
+            },
+            Condition::Malleable => ConditionEffect {
+                progress_multiplier: 150,
+                ..normal
+            },
+            Condition::Primed => ConditionEffect {
+                buff_duration_bonus: 2,
+                ..normal
+            },
+        }
+    }
+
+    /// The condition that always follows this one, if any
+    pub fn next(self) -> Option<Condition> {
+        match self {
+            Condition::Excellent => Some(Condition::Poor),
+            Condition::GoodOmen => Some(Condition::Good),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+// Approximate chance of each condition being rolled for a step, from community datamining.
+// Whatever isn't covered by the listed conditions goes to Normal.
+const NORMAL_RECIPE_CHANCES: [(Condition, f64); 2] =
+    [(Condition::Good, 0.25), (Condition::Excellent, 0.04)];
+const EXPERT_RECIPE_CHANCES: [(Condition, f64); 7] = [
+    (Condition::Good, 0.12),
+    (Condition::Centered, 0.15),
+    (Condition::Sturdy, 0.15),
+    (Condition::Pliant, 0.12),
+    (Condition::Malleable, 0.12),
+    (Condition::Primed, 0.12),
+    (Condition::GoodOmen, 0.12),
+];
+
+/// The set of conditions that can occur during a recipe, decoded from `ConditionsFlag`.
+/// Serialized as a list of condition names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Conditions(u32);
+
+impl Conditions {
+    /// Decodes a `ConditionsFlag`, ignoring bits that don't match a known condition
+    pub fn from_flag(flag: u32) -> Self {
+        let known = Condition::ALL
+            .iter()
+            .fold(0, |bits, condition| bits | condition.bit());
+        Conditions(flag & known)
+    }
+
+    pub fn bits(self) -> u32 {
+        self.0
+    }
+
+    pub fn contains(self, condition: Condition) -> bool {
+        self.0 & condition.bit() != 0
+    }
+
+    pub fn iter(self) -> impl Iterator<Item = Condition> {
+        Condition::ALL
+            .into_iter()
+            .filter(move |&condition| self.contains(condition))
+    }
+
+    /// Chance of each condition being rolled for a step, for conditions in this set. Normal
+    /// gets the remaining chance. Forced transitions (see [`Condition::next`]) aren't included.
+    pub fn chances(self, is_expert: bool) -> Vec<(Condition, f64)> {
+        let table: &[(Condition, f64)] = if is_expert {
+            &EXPERT_RECIPE_CHANCES
+        } else {
+            &NORMAL_RECIPE_CHANCES
+        };
+
+        let mut chances: Vec<(Condition, f64)> = table
+            .iter()
+            .copied()
+            .filter(|&(condition, _)| self.contains(condition))
+            .collect();
+        let normal = 1.0 - chances.iter().map(|(_, chance)| chance).sum::<f64>();
+        chances.insert(0, (Condition::Normal, normal));
+        chances
+    }
+}
+
+impl FromIterator<Condition> for Conditions {
+    fn from_iter<I: IntoIterator<Item = Condition>>(conditions: I) -> Self {
+        Conditions(
+            conditions
+                .into_iter()
+                .fold(0, |bits, condition| bits | condition.bit()),
+        )
+    }
+}
+
+impl Serialize for Conditions {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for Conditions {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Vec::<Condition>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
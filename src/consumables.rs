@@ -1,8 +1,12 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
 
-use crate::models::{ItemActionRecord, ItemFoodRecord, ItemRecord};
-use crate::utils::{read_csv_data, write_json_file};
+use crate::error::{Error, Result};
+use crate::game_data::GameData;
+use crate::models::{ItemActionRecord, ItemFoodParam, ItemFoodRecord, ItemRecord};
+use crate::utils::write_json_file;
+use crate::validate::SheetSchema;
 
 // https://github.com/xivapi/ffxiv-datamining/blob/35e435494317723be856f18fb3b48f526316656e/docs/ItemActions.md#845
 const ITEM_ACTION_BATTLE_FOOD_TYPE_ID: u32 = 844;
@@ -20,122 +24,130 @@ const CONTROL_PARAM_ID: u32 = 71;
 const CP_PARAM_ID: u32 = 11;
 const VALID_PARAMS: &[u32] = &[CRAFTSMANSHIP_PARAM_ID, CONTROL_PARAM_ID, CP_PARAM_ID];
 
-pub fn build_consumables() -> HashMap<u32, String> {
+pub fn sheets() -> Vec<SheetSchema> {
+    vec![
+        SheetSchema::of::<ItemFoodRecord>(),
+        SheetSchema::of::<ItemActionRecord>(),
+        SheetSchema::of::<ItemRecord>(),
+    ]
+}
+
+pub struct Consumables {
+    pub meals: Vec<ConsumableOutput>,
+    pub potions: Vec<ConsumableOutput>,
+    /// English names of every meal and potion, keyed by item id
+    pub item_names: HashMap<u32, String>,
+}
+
+pub fn build_consumables(data: &GameData) -> Result<Consumables> {
     let mut relevant_items = HashMap::new();
+    let item_foods = data.item_foods()?;
 
-    let mut item_food_by_id = HashMap::new();
-    for item_food in read_csv_data::<ItemFoodRecord>("data/ItemFood.csv") {
-        item_food_by_id.insert(item_food.id, item_food);
-    }
+    // only food and potion actions are looked up, so a bad link from an item that can't be
+    // consumed doesn't stop consumables from being built
+    let consumable_actions: HashMap<u32, &ItemActionRecord> = data
+        .item_actions()?
+        .iter()
+        .filter(|item_action| VALID_ITEM_ACTION_TYPE_IDS.contains(&item_action.type_id))
+        .map(|item_action| (item_action.id, item_action))
+        .collect();
 
-    let mut consumable_by_item_action_id = HashMap::new();
-    for item_action in read_csv_data::<ItemActionRecord>("data/ItemAction.csv") {
-        if !VALID_ITEM_ACTION_TYPE_IDS.contains(&item_action.type_id) {
+    let mut meals = vec![];
+    let mut potions = vec![];
+    for item in data.items()? {
+        let Some(item_action) = consumable_actions.get(&item.item_action) else {
+            continue;
+        };
+
+        let item_food = item_action.item_food(data)?;
+        if !item_food
+            .params
+            .iter()
+            .any(|param| VALID_PARAMS.contains(&param.param))
+        {
             continue;
         }
 
-        let is_potion = item_action.type_id == ITEM_ACTION_DOH_POTION_TYPE_ID;
-        let item_food = *item_food_by_id.get(&item_action.data_1).unwrap();
-
-        let params: &[u32] = &[item_food.param_0, item_food.param_1, item_food.param_2];
-        if params.iter().any(|param| VALID_PARAMS.contains(param)) {
-            consumable_by_item_action_id.insert(
-                item_action.id,
-                Consumable {
-                    is_potion,
-                    item_food,
-                },
-            );
-        }
-    }
+        relevant_items.insert(item.id, item.name.clone());
 
-    let mut meals = vec![];
-    let mut potions = vec![];
-    for item in read_csv_data::<ItemRecord>("data/Item.csv") {
-        if let Some(consumable) = consumable_by_item_action_id.get(&item.item_action) {
-            relevant_items.insert(item.id, item.name.clone());
-
-            let Consumable {
-                is_potion,
-                item_food,
-            } = *consumable;
-
-            let (craftsmanship, control, cp) = get_stats(item_food);
-
-            let consumable = ConsumableOutput {
-                item_level: item.item_level,
-                name: item.name,
-                craftsmanship,
-                control,
-                cp,
-            };
-
-            if is_potion {
-                potions.push(consumable);
-            } else {
-                meals.push(consumable);
-            }
+        let (craftsmanship, control, cp) = get_stats(item_food)
+            .map_err(|message| Error::bad_row(item_foods.path(), item_food.id, message))?;
+
+        let consumable = ConsumableOutput {
+            item_level: item.item_level,
+            name: item.name.clone(),
+            craftsmanship,
+            control,
+            cp,
+        };
+
+        if item_action.type_id == ITEM_ACTION_DOH_POTION_TYPE_ID {
+            potions.push(consumable);
+        } else {
+            meals.push(consumable);
         }
     }
 
-    meals.sort_by(|a, b| a.item_level.cmp(&b.item_level));
-    potions.sort_by(|a, b| a.item_level.cmp(&b.item_level));
-    write_json_file(&meals, "output/meals.json");
-    write_json_file(&potions, "output/potions.json");
+    meals.sort_by_key(|meal| meal.item_level);
+    potions.sort_by_key(|potion| potion.item_level);
 
-    relevant_items
+    Ok(Consumables {
+        meals,
+        potions,
+        item_names: relevant_items,
+    })
 }
 
-#[allow(clippy::type_complexity)]
-#[rustfmt::skip]
-fn get_stats(item_food: ItemFoodRecord) -> (Option<Vec<u32>>, Option<Vec<u32>>, Option<Vec<u32>>) {
-    let ItemFoodRecord {
-        id: _,
-        param_0, param_0_relative, param_0_value, param_0_max, param_0_hq_value, param_0_hq_max,
-        param_1, param_1_relative, param_1_value, param_1_max, param_1_hq_value, param_1_hq_max,
-        param_2, param_2_relative, param_2_value, param_2_max, param_2_hq_value, param_2_hq_max,
-    } = item_food;
+pub fn write_consumables(consumables: &Consumables, output_dir: &Path) -> Result<()> {
+    write_json_file(&consumables.meals, output_dir.join("meals.json"))?;
+    write_json_file(&consumables.potions, output_dir.join("potions.json"))
+}
 
+#[allow(clippy::type_complexity)]
+fn get_stats(
+    item_food: &ItemFoodRecord,
+) -> std::result::Result<(Option<Vec<u32>>, Option<Vec<u32>>, Option<Vec<u32>>), String> {
     let mut craftsmanship = None;
     let mut control = None;
     let mut cp = None;
 
-    for (param, is_relative, value, max, hq_value, hq_max) in [
-        (param_0, param_0_relative, param_0_value, param_0_max, param_0_hq_value, param_0_hq_max),
-        (param_1, param_1_relative, param_1_value, param_1_max, param_1_hq_value, param_1_hq_max),
-        (param_2, param_2_relative, param_2_value, param_2_max, param_2_hq_value, param_2_hq_max),
-    ] {
-        let values = vec![value as u32, max, hq_value as u32, hq_max];
+    for &ItemFoodParam {
+        param,
+        relative,
+        value,
+        max,
+        hq_value,
+        hq_max,
+    } in &item_food.params
+    {
+        let stat = match param {
+            CRAFTSMANSHIP_PARAM_ID => &mut craftsmanship,
+            CONTROL_PARAM_ID => &mut control,
+            CP_PARAM_ID => &mut cp,
+            _ => continue,
+        };
+
+        if !relative {
+            return Err(format!("BaseParam {param} isn't relative"));
+        }
 
-        if param == CRAFTSMANSHIP_PARAM_ID {
-            assert!(is_relative);
-            assert!(values.iter().all(|&v| v > 0));
-            craftsmanship = Some(values);
-        } else if param == CONTROL_PARAM_ID {
-            assert!(is_relative);
-            assert!(values.iter().all(|&v| v > 0));
-            control = Some(values);
-        } else if param == CP_PARAM_ID {
-            assert!(is_relative);
-            assert!(values.iter().all(|&v| v > 0));
-            cp = Some(values);
+        let values = vec![value as u32, max, hq_value as u32, hq_max];
+        if !values.iter().all(|&v| v > 0) {
+            return Err(format!("BaseParam {param} has empty values {values:?}"));
         }
-    }
 
-    (craftsmanship, control, cp)
-}
+        *stat = Some(values);
+    }
 
-#[derive(Debug)]
-struct Consumable {
-    is_potion: bool,
-    item_food: ItemFoodRecord,
+    Ok((craftsmanship, control, cp))
 }
 
-#[derive(Debug, Serialize)]
-struct ConsumableOutput {
-    item_level: u32,
-    name: String,
-    craftsmanship: Option<Vec<u32>>,
-    control: Option<Vec<u32>>,
-    cp: Option<Vec<u32>>,
+/// Stat bonuses are `[value, max, hq_value, hq_max]`, where values are percentages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsumableOutput {
+    pub item_level: u32,
+    pub name: String,
+    pub craftsmanship: Option<Vec<u32>>,
+    pub control: Option<Vec<u32>>,
+    pub cp: Option<Vec<u32>>,
 }
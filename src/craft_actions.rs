@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use crate::error::Result;
+use crate::game_data::GameData;
+use crate::models::{ActionRecord, ClassJobRecord, CraftActionRecord};
+use crate::utils::write_json_file;
+use crate::validate::SheetSchema;
+
+pub fn sheets() -> Vec<SheetSchema> {
+    vec![
+        SheetSchema::of::<ActionRecord>(),
+        SheetSchema::of::<ClassJobRecord>(),
+        SheetSchema::of::<CraftActionRecord>(),
+    ]
+}
+
+/// Builds one entry per crafting action, merging the separate rows that each job has for it.
+/// Most actions are in CraftAction.csv, but buffs like Veneration are in Action.csv.
+pub fn build_craft_actions(data: &GameData) -> Result<Vec<CraftActionOutput>> {
+    // every job's row for an action lists the same per-job ids, so the first job's id
+    // identifies it
+    let mut actions_by_id: HashMap<u32, CraftActionOutput> = HashMap::new();
+    for craft_action in data.craft_actions()? {
+        if !craft_action.is_used() {
+            continue;
+        }
+
+        let action_id = craft_action.action_id(data)?;
+        if actions_by_id.contains_key(&action_id) {
+            continue;
+        }
+        let numbers = match craft_action.job_id(data)? {
+            Some(job_id) => craft_action.description.numbers_by_level(job_id),
+            None => BTreeMap::new(),
+        };
+        actions_by_id.insert(
+            action_id,
+            CraftActionOutput {
+                name: craft_action.name.clone(),
+                level: craft_action.level,
+                cp_cost: craft_action.cost,
+                is_specialist: craft_action.specialist,
+                quest_requirement: craft_action.quest_requirement,
+                ids: craft_action
+                    .job_ids(data)?
+                    .into_iter()
+                    .map(|(job, id)| (job.id, id))
+                    .collect(),
+                numbers,
+            },
+        );
+    }
+
+    // Action.csv doesn't list the other jobs' ids, so its rows are merged by name
+    let crafting_jobs = data.crafting_jobs()?;
+    let mut actions_by_name: HashMap<&str, CraftActionOutput> = HashMap::new();
+    for action in data.actions()? {
+        // ActionCategory 7 is the Disciples of the Hand's abilities
+        if action.action_category != 7 || !action.is_player_action || action.name.is_empty() {
+            continue;
+        }
+        let Some(job) = u32::try_from(action.class_job)
+            .ok()
+            .and_then(|job_id| crafting_jobs.get(job_id))
+        else {
+            continue;
+        };
+
+        actions_by_name
+            .entry(&action.name)
+            .or_insert_with(|| CraftActionOutput {
+                name: action.name.clone(),
+                level: action.level,
+                cp_cost: action.cost,
+                is_specialist: false,
+                quest_requirement: 0,
+                ids: BTreeMap::new(),
+                numbers: BTreeMap::new(),
+            })
+            .ids
+            .entry(job.id)
+            .or_insert(action.id);
+    }
+
+    let mut craft_actions: Vec<CraftActionOutput> = actions_by_id
+        .into_values()
+        .chain(actions_by_name.into_values())
+        .collect();
+    craft_actions.sort_by(|a, b| (a.level, &a.name).cmp(&(b.level, &b.name)));
+
+    Ok(craft_actions)
+}
+
+pub fn write_craft_actions(craft_actions: &[CraftActionOutput], output_dir: &Path) -> Result<()> {
+    write_json_file(&craft_actions, output_dir.join("actions_data.json"))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CraftActionOutput {
+    pub name: String,
+    pub level: u32,
+    pub cp_cost: u32,
+    pub is_specialist: bool,
+    /// Quest that unlocks the action, or 0. Only CraftAction.csv lists these.
+    pub quest_requirement: u32,
+    /// The action's id for each job, by ClassJob id
+    pub ids: BTreeMap<u32, u32>,
+    /// Numbers from the English description's `Label: value` lines, like efficiency and
+    /// success rate, keyed by the level they apply from. Action.csv buffs have none.
+    pub numbers: BTreeMap<u32, BTreeMap<String, u32>>,
+}
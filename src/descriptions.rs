@@ -0,0 +1,141 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::error::Result;
+use crate::game_data::GameData;
+use crate::icons::build_status_names;
+use crate::markup::{labeled_numbers, Format, Parameters};
+use crate::models::{ClassJobRecord, CraftActionRecord, StatusRecord};
+use crate::translated_items::language_tag;
+use crate::utils::write_json_file;
+use crate::validate::SheetSchema;
+
+pub fn sheets(languages: &[String]) -> Vec<SheetSchema> {
+    let mut sheets = vec![
+        SheetSchema::of::<ClassJobRecord>(),
+        SheetSchema::of::<CraftActionRecord>(),
+        SheetSchema::of::<StatusRecord>(),
+    ];
+    for language in languages {
+        sheets.push(SheetSchema::localized::<CraftActionRecord>(language));
+        sheets.push(SheetSchema::localized::<StatusRecord>(language));
+    }
+    sheets
+}
+
+/// A description, from the level it applies at until the next one's level
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LevelDescription {
+    pub level: u32,
+    pub description: String,
+    /// Numbers from the description's `Label: value` lines at this level, like efficiency
+    /// and success rate, keyed by their label in the description's language
+    pub numbers: BTreeMap<String, u32>,
+}
+
+/// Crafting action and status descriptions in one language, keyed by English name
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Descriptions {
+    pub actions: BTreeMap<String, Vec<LevelDescription>>,
+    pub statuses: BTreeMap<String, String>,
+}
+
+pub struct AllDescriptions {
+    pub english: Descriptions,
+    pub by_language: BTreeMap<String, Descriptions>,
+}
+
+pub fn build_descriptions(data: &GameData, format: Format) -> Result<AllDescriptions> {
+    // one row per action, since every job's row has the same text for its own job. Ids are
+    // sorted so that when names repeat, the newest row wins.
+    let mut english_actions: BTreeMap<u32, (&CraftActionRecord, u32)> = BTreeMap::new();
+    for craft_action in data.craft_actions()? {
+        if !craft_action.is_used() {
+            continue;
+        }
+        let Some(job_id) = craft_action.job_id(data)? else {
+            continue;
+        };
+        english_actions
+            .entry(craft_action.action_id(data)?)
+            .or_insert((craft_action, job_id));
+    }
+    let english_statuses: BTreeMap<u32, String> = build_status_names(data)?.into_iter().collect();
+
+    let describe = |sheets: &GameData| -> Result<Descriptions> {
+        let mut descriptions = Descriptions::default();
+
+        let craft_actions = sheets.craft_actions()?;
+        for &(english_action, job_id) in english_actions.values() {
+            let Some(craft_action) = craft_actions.get(english_action.id) else {
+                continue;
+            };
+            let levels = craft_action
+                .description
+                .by_level(job_id, format)
+                .into_iter()
+                .map(|(level, description)| {
+                    let plain = craft_action
+                        .description
+                        .render(&Parameters::for_job(job_id, level));
+                    LevelDescription {
+                        level,
+                        description,
+                        numbers: labeled_numbers(&plain),
+                    }
+                })
+                .collect();
+            descriptions
+                .actions
+                .insert(english_action.name.clone(), levels);
+        }
+
+        let statuses = sheets.statuses()?;
+        for (id, english_name) in &english_statuses {
+            let Some(status) = statuses.get(*id) else {
+                continue;
+            };
+            let description = status.description.format(&Parameters::new(), format);
+            descriptions
+                .statuses
+                .insert(english_name.clone(), description);
+        }
+
+        Ok(descriptions)
+    };
+
+    let mut by_language = BTreeMap::new();
+    for (language, localized) in data.languages() {
+        by_language.insert(String::from(language), describe(localized)?);
+    }
+
+    Ok(AllDescriptions {
+        english: describe(data)?,
+        by_language,
+    })
+}
+
+/// Writes `action_descriptions.json` and `status_descriptions.json`, plus a
+/// `_{language tag}` suffixed copy of each per language
+pub fn write_descriptions(descriptions: &AllDescriptions, output_dir: &Path) -> Result<()> {
+    let languages = std::iter::once((String::new(), &descriptions.english)).chain(
+        descriptions
+            .by_language
+            .iter()
+            .map(|(language, descriptions)| (format!("_{}", language_tag(language)), descriptions)),
+    );
+
+    for (suffix, descriptions) in languages {
+        write_json_file(
+            &descriptions.actions,
+            output_dir.join(format!("action_descriptions{suffix}.json")),
+        )?;
+        write_json_file(
+            &descriptions.statuses,
+            output_dir.join(format!("status_descriptions{suffix}.json")),
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use std::fmt::Write;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("{}: missing column `{column}`", file.display())]
+    MissingColumn { file: PathBuf, column: String },
+
+    #[error("{}: {message}", location(file, *line, id.as_deref()))]
+    BadRow {
+        file: PathBuf,
+        /// Line in the file, counting header rows and starting from 1
+        line: Option<u64>,
+        id: Option<String>,
+        message: String,
+    },
+
+    #[error(
+        "{}: `{column}` refers to {target_id}, which doesn't exist in {target}.csv",
+        location(file, None, Some(&id.to_string()))
+    )]
+    DanglingKey {
+        file: PathBuf,
+        id: u32,
+        column: String,
+        target: &'static str,
+        target_id: u32,
+    },
+
+    #[error("{}: no {language} translation for {english_name:?}", location(file, None, Some(&id.to_string())))]
+    MissingTranslation {
+        file: PathBuf,
+        language: String,
+        id: u32,
+        english_name: String,
+    },
+
+    #[error("{} sheet(s) don't match their expected columns: {}", files.len(), list_files(files))]
+    InvalidSheets { files: Vec<PathBuf> },
+
+    #[error("{}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+}
+
+impl Error {
+    pub(crate) fn io(path: impl AsRef<Path>, source: impl Into<io::Error>) -> Self {
+        Error::Io {
+            path: path.as_ref().to_path_buf(),
+            source: source.into(),
+        }
+    }
+
+    pub(crate) fn bad_row(file: impl AsRef<Path>, id: u32, message: impl Into<String>) -> Self {
+        Error::BadRow {
+            file: file.as_ref().to_path_buf(),
+            line: None,
+            id: Some(id.to_string()),
+            message: message.into(),
+        }
+    }
+}
+
+fn list_files(files: &[PathBuf]) -> String {
+    files
+        .iter()
+        .map(|file| file.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn location(file: &Path, line: Option<u64>, id: Option<&str>) -> String {
+    let mut location = file.display().to_string();
+    match (line, id) {
+        (Some(line), Some(id)) => write!(location, " (line {line}, id {id})"),
+        (Some(line), None) => write!(location, " (line {line})"),
+        (None, Some(id)) => write!(location, " (id {id})"),
+        (None, None) => Ok(()),
+    }
+    .unwrap();
+    location
+}
@@ -0,0 +1,92 @@
+use crate::models::RecipeLevelRecord;
+use crate::recipes::RecipeOutput;
+
+/// The parts of a recipe that scale a crafter's stats into progress and quality
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecipeParams {
+    pub job_level: u32,
+    pub progress_div: u32,
+    pub progress_mod: u32,
+    pub quality_div: u32,
+    pub quality_mod: u32,
+}
+
+impl From<&RecipeOutput> for RecipeParams {
+    fn from(recipe: &RecipeOutput) -> Self {
+        RecipeParams {
+            job_level: recipe.job_level,
+            progress_div: recipe.progress_div,
+            progress_mod: recipe.progress_mod,
+            quality_div: recipe.quality_div,
+            quality_mod: recipe.quality_mod,
+        }
+    }
+}
+
+impl From<&RecipeLevelRecord> for RecipeParams {
+    fn from(recipe_level: &RecipeLevelRecord) -> Self {
+        RecipeParams {
+            job_level: recipe_level.job_level,
+            progress_div: recipe_level.progress_divider,
+            progress_mod: recipe_level.progress_modifier,
+            quality_div: recipe_level.quality_divider,
+            quality_mod: recipe_level.quality_modifier,
+        }
+    }
+}
+
+/// Progress and quality added by an action with 100% efficiency, before conditions and buffs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BaseValues {
+    pub progress: u32,
+    pub quality: u32,
+}
+
+pub fn base_values(
+    recipe: impl Into<RecipeParams>,
+    crafter_level: u32,
+    craftsmanship: u32,
+    control: u32,
+) -> BaseValues {
+    let recipe = recipe.into();
+    BaseValues {
+        progress: base_progress(recipe, crafter_level, craftsmanship),
+        quality: base_quality(recipe, crafter_level, control),
+    }
+}
+
+/// `craftsmanship * 10 / progress_div + 2`, reduced by `progress_mod` unless the crafter
+/// is above the recipe's level
+pub fn base_progress(
+    recipe: impl Into<RecipeParams>,
+    crafter_level: u32,
+    craftsmanship: u32,
+) -> u32 {
+    let recipe = recipe.into();
+    let progress = (craftsmanship * 10)
+        .checked_div(recipe.progress_div)
+        .unwrap_or(0)
+        + 2;
+    if crafter_level <= recipe.job_level {
+        apply_percent(progress, recipe.progress_mod)
+    } else {
+        progress
+    }
+}
+
+/// `control * 10 / quality_div + 35`, reduced by `quality_mod` unless the crafter is above
+/// the recipe's level
+pub fn base_quality(recipe: impl Into<RecipeParams>, crafter_level: u32, control: u32) -> u32 {
+    let recipe = recipe.into();
+    let quality = (control * 10).checked_div(recipe.quality_div).unwrap_or(0) + 35;
+    if crafter_level <= recipe.job_level {
+        apply_percent(quality, recipe.quality_mod)
+    } else {
+        quality
+    }
+}
+
+/// Scales a value by a percentage, rounding down like the game does after each step
+pub fn apply_percent(value: u32, percent: u32) -> u32 {
+    value * percent / 100
+}
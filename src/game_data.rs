@@ -0,0 +1,450 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use crate::error::{Error, Result};
+use crate::models::{
+    ActionRecord, ClassJobRecord, CraftActionRecord, ItemActionRecord, ItemFoodRecord, ItemRecord,
+    RecipeLevelRecord, RecipeLookupRecord, RecipeRecord, Sheet, StatusRecord,
+};
+use crate::utils::read_csv_data;
+
+/// Every row of a sheet, indexed by id
+#[derive(Debug)]
+pub struct SheetTable<T> {
+    path: PathBuf,
+    rows: Vec<T>,
+    index: HashMap<u32, usize>,
+}
+
+impl<T: Sheet> SheetTable<T> {
+    fn read(path: PathBuf) -> Result<Self> {
+        let rows = read_csv_data::<T>(&path)?;
+        let index = rows
+            .iter()
+            .enumerate()
+            .map(|(position, row)| (row.id(), position))
+            .collect();
+        Ok(SheetTable { path, rows, index })
+    }
+
+    /// The file the sheet was read from
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn get(&self, id: u32) -> Option<&T> {
+        self.index.get(&id).map(|&position| &self.rows[position])
+    }
+
+    /// Rows in file order
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.rows.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Looks up the row that `column` of the `source` row `source_id` refers to
+    fn link<S: Sheet>(
+        &self,
+        source: &SheetTable<S>,
+        source_id: u32,
+        column: &str,
+        id: u32,
+    ) -> Result<&T> {
+        self.get(id).ok_or_else(|| Error::DanglingKey {
+            file: source.path.clone(),
+            id: source_id,
+            column: String::from(column),
+            target: T::NAME,
+            target_id: id,
+        })
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SheetTable<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.rows.iter()
+    }
+}
+
+/// The crafting jobs in game order. They're read once from the English ClassJob sheet, since
+/// sheets like RecipeLookup and CraftAction name their per-job columns after the jobs'
+/// English abbreviations, even in other languages.
+#[derive(Debug)]
+pub struct CraftingJobs {
+    jobs: Vec<ClassJobRecord>,
+    /// Positions in `jobs`, by `DohDolJobIndex`
+    by_craft_type: HashMap<u32, usize>,
+}
+
+impl CraftingJobs {
+    fn new(class_jobs: &SheetTable<ClassJobRecord>) -> Self {
+        let mut jobs: Vec<_> = class_jobs
+            .iter()
+            .filter(|job| job.is_crafter())
+            .cloned()
+            .collect();
+        jobs.sort_by_key(|job| job.doh_dol_index);
+        let by_craft_type = jobs
+            .iter()
+            .enumerate()
+            .map(|(position, job)| (job.doh_dol_index as u32, position))
+            .collect();
+        CraftingJobs {
+            jobs,
+            by_craft_type,
+        }
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, ClassJobRecord> {
+        self.jobs.iter()
+    }
+
+    /// The job that recipes with the given `CraftType` are for
+    pub fn by_craft_type(&self, craft_type: u32) -> Option<&ClassJobRecord> {
+        self.by_craft_type
+            .get(&craft_type)
+            .map(|&position| &self.jobs[position])
+    }
+
+    /// The job's position in game order
+    pub fn position(&self, job_id: u32) -> Option<usize> {
+        self.jobs.iter().position(|job| job.id == job_id)
+    }
+
+    /// The crafting job with the given ClassJob id
+    pub fn get(&self, job_id: u32) -> Option<&ClassJobRecord> {
+        self.position(job_id).map(|position| &self.jobs[position])
+    }
+}
+
+impl<'a> IntoIterator for &'a CraftingJobs {
+    type Item = &'a ClassJobRecord;
+    type IntoIter = std::slice::Iter<'a, ClassJobRecord>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.jobs.iter()
+    }
+}
+
+/// The game sheets in a data directory. Each sheet is read the first time it's used, and
+/// then shared by every stage that needs it.
+#[derive(Debug, Default)]
+pub struct GameData {
+    data_dir: PathBuf,
+    /// The same sheets in other languages, read from `data_dir/{language}`
+    languages: BTreeMap<String, GameData>,
+    /// For localized sheets, the English data directory that crafting jobs are read from
+    english_dir: Option<PathBuf>,
+    /// Shared with the localized sheets
+    crafting_jobs: Arc<OnceLock<CraftingJobs>>,
+
+    actions: OnceLock<SheetTable<ActionRecord>>,
+    class_jobs: OnceLock<SheetTable<ClassJobRecord>>,
+    craft_actions: OnceLock<SheetTable<CraftActionRecord>>,
+    item_actions: OnceLock<SheetTable<ItemActionRecord>>,
+    item_foods: OnceLock<SheetTable<ItemFoodRecord>>,
+    items: OnceLock<SheetTable<ItemRecord>>,
+    recipes: OnceLock<SheetTable<RecipeRecord>>,
+    recipe_levels: OnceLock<SheetTable<RecipeLevelRecord>>,
+    recipe_lookups: OnceLock<SheetTable<RecipeLookupRecord>>,
+    statuses: OnceLock<SheetTable<StatusRecord>>,
+}
+
+impl GameData {
+    pub fn new(data_dir: impl Into<PathBuf>) -> Self {
+        GameData {
+            data_dir: data_dir.into(),
+            ..GameData::default()
+        }
+    }
+
+    /// Adds localized sheets for each language, read from subdirectories of the data directory
+    pub fn with_languages<'a>(mut self, languages: impl IntoIterator<Item = &'a str>) -> Self {
+        for language in languages {
+            let localized = GameData {
+                english_dir: Some(self.data_dir.clone()),
+                crafting_jobs: Arc::clone(&self.crafting_jobs),
+                ..GameData::new(self.data_dir.join(language))
+            };
+            self.languages.insert(String::from(language), localized);
+        }
+        self
+    }
+
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+
+    /// Localized sheets, keyed by language
+    pub fn languages(&self) -> impl Iterator<Item = (&str, &GameData)> {
+        self.languages
+            .iter()
+            .map(|(language, data)| (language.as_str(), data))
+    }
+
+    pub fn actions(&self) -> Result<&SheetTable<ActionRecord>> {
+        self.load(&self.actions)
+    }
+
+    pub fn class_jobs(&self) -> Result<&SheetTable<ClassJobRecord>> {
+        self.load(&self.class_jobs)
+    }
+
+    /// ClassJob, or `None` if the data directory doesn't have it. Languages can do without
+    /// it, and keep the English job names.
+    pub fn optional_class_jobs(&self) -> Result<Option<&SheetTable<ClassJobRecord>>> {
+        let file = self.data_dir.join(format!("{}.csv", ClassJobRecord::NAME));
+        if self.class_jobs.get().is_none() && !file.is_file() {
+            return Ok(None);
+        }
+        self.class_jobs().map(Some)
+    }
+
+    /// Crafting jobs, in game order, as named in English
+    pub fn crafting_jobs(&self) -> Result<&CraftingJobs> {
+        if let Some(jobs) = self.crafting_jobs.get() {
+            return Ok(jobs);
+        }
+        let jobs = match &self.english_dir {
+            Some(english_dir) => CraftingJobs::new(&SheetTable::read(
+                english_dir.join(format!("{}.csv", ClassJobRecord::NAME)),
+            )?),
+            None => CraftingJobs::new(self.class_jobs()?),
+        };
+        Ok(self.crafting_jobs.get_or_init(|| jobs))
+    }
+
+    pub fn craft_actions(&self) -> Result<&SheetTable<CraftActionRecord>> {
+        self.load(&self.craft_actions)
+    }
+
+    pub fn item_actions(&self) -> Result<&SheetTable<ItemActionRecord>> {
+        self.load(&self.item_actions)
+    }
+
+    pub fn item_foods(&self) -> Result<&SheetTable<ItemFoodRecord>> {
+        self.load(&self.item_foods)
+    }
+
+    pub fn items(&self) -> Result<&SheetTable<ItemRecord>> {
+        self.load(&self.items)
+    }
+
+    pub fn recipes(&self) -> Result<&SheetTable<RecipeRecord>> {
+        self.load(&self.recipes)
+    }
+
+    pub fn recipe_levels(&self) -> Result<&SheetTable<RecipeLevelRecord>> {
+        self.load(&self.recipe_levels)
+    }
+
+    pub fn recipe_lookups(&self) -> Result<&SheetTable<RecipeLookupRecord>> {
+        self.load(&self.recipe_lookups)
+    }
+
+    pub fn statuses(&self) -> Result<&SheetTable<StatusRecord>> {
+        self.load(&self.statuses)
+    }
+
+    fn load<'a, T: Sheet>(&self, sheet: &'a OnceLock<SheetTable<T>>) -> Result<&'a SheetTable<T>> {
+        if let Some(table) = sheet.get() {
+            return Ok(table);
+        }
+        let table = SheetTable::read(self.data_dir.join(format!("{}.csv", T::NAME)))?;
+        Ok(sheet.get_or_init(|| table))
+    }
+}
+
+impl ClassJobRecord {
+    /// ClassJobCategory 33 is the Disciples of the Hand
+    pub fn is_crafter(&self) -> bool {
+        self.category == 33 && self.doh_dol_index >= 0
+    }
+}
+
+impl RecipeRecord {
+    /// The crafting job that the recipe is for
+    pub fn job<'a>(&self, data: &'a GameData) -> Result<&'a ClassJobRecord> {
+        match data.crafting_jobs()?.by_craft_type(self.craft_type) {
+            Some(job) => Ok(job),
+            None => Err(Error::bad_row(
+                data.recipes()?.path(),
+                self.id,
+                format!(
+                    "`CraftType` {} isn't a crafting job in ClassJob.csv",
+                    self.craft_type
+                ),
+            )),
+        }
+    }
+
+    /// The crafted item, or `None` for unused recipe rows
+    pub fn result_item<'a>(&self, data: &'a GameData) -> Result<Option<&'a ItemRecord>> {
+        if self.result_item_id == 0 {
+            return Ok(None);
+        }
+        let item = data.items()?.link(
+            data.recipes()?,
+            self.id,
+            "Item{Result}",
+            self.result_item_id,
+        )?;
+        Ok(Some(item))
+    }
+
+    pub fn level<'a>(&self, data: &'a GameData) -> Result<&'a RecipeLevelRecord> {
+        data.recipe_levels()?.link(
+            data.recipes()?,
+            self.id,
+            "RecipeLevelTable",
+            self.recipe_level,
+        )
+    }
+
+    /// Each ingredient item and the amount needed, skipping empty ingredient slots
+    pub fn ingredient_items<'a>(&self, data: &'a GameData) -> Result<Vec<(&'a ItemRecord, u32)>> {
+        let mut ingredients = vec![];
+        for ingredient in &self.ingredients {
+            if ingredient.item <= 0 || ingredient.amount == 0 {
+                continue;
+            }
+            let item = data.items()?.link(
+                data.recipes()?,
+                self.id,
+                "Item{Ingredient}",
+                ingredient.item as u32,
+            )?;
+            ingredients.push((item, ingredient.amount));
+        }
+        Ok(ingredients)
+    }
+}
+
+impl ItemRecord {
+    /// The action used when the item is consumed, if it has one
+    pub fn item_action<'a>(&self, data: &'a GameData) -> Result<Option<&'a ItemActionRecord>> {
+        if self.item_action == 0 {
+            return Ok(None);
+        }
+        let item_action =
+            data.item_actions()?
+                .link(data.items()?, self.id, "ItemAction", self.item_action)?;
+        Ok(Some(item_action))
+    }
+}
+
+impl ItemActionRecord {
+    /// The stat bonuses of a food or potion action, whose `Data[1]` refers to `ItemFood`.
+    /// For other action types `Data[1]` means something else, so check `type_id` first.
+    pub fn item_food<'a>(&self, data: &'a GameData) -> Result<&'a ItemFoodRecord> {
+        data.item_foods()?
+            .link(data.item_actions()?, self.id, "Data[1]", self.data_1)
+    }
+}
+
+impl CraftActionRecord {
+    /// Rows with a `ClassJob` of -1 are unused, or left over from older versions of an
+    /// action. Some current actions, like Daring Touch, have 0 instead of their job.
+    pub fn is_used(&self) -> bool {
+        self.class_job >= 0 && !self.name.is_empty()
+    }
+
+    /// The crafting job the row is for. Rows with a `ClassJob` of 0 are for the job whose
+    /// column holds the row's id.
+    pub fn job_id(&self, data: &GameData) -> Result<Option<u32>> {
+        if let Ok(job_id @ 1..) = u32::try_from(self.class_job) {
+            return Ok(Some(job_id));
+        }
+        let job_ids = self.job_ids(data)?;
+        Ok(job_ids
+            .into_iter()
+            .find(|&(_, id)| id == self.id)
+            .map(|(job, _)| job.id))
+    }
+
+    /// The action's id for each crafting job, in game order
+    pub fn job_ids<'a>(&self, data: &'a GameData) -> Result<Vec<(&'a ClassJobRecord, u32)>> {
+        job_columns(
+            data.crafting_jobs()?,
+            data.craft_actions()?,
+            self.id,
+            &self.columns,
+        )
+    }
+
+    /// The id that identifies the action across jobs, which is the first crafting job's id
+    /// for it
+    pub fn action_id(&self, data: &GameData) -> Result<u32> {
+        let job_ids = self.job_ids(data)?;
+        Ok(job_ids.first().map_or(self.id, |&(_, id)| id))
+    }
+}
+
+impl RecipeLookupRecord {
+    /// Recipe ids for each crafting job that has a recipe for the item, in game order
+    pub fn recipe_ids<'a>(&self, data: &'a GameData) -> Result<Vec<(&'a ClassJobRecord, u32)>> {
+        job_columns(
+            data.crafting_jobs()?,
+            data.recipe_lookups()?,
+            self.id,
+            &self.columns,
+        )
+    }
+
+    /// The recipes for the looked up item
+    pub fn recipes<'a>(&self, data: &'a GameData) -> Result<Vec<&'a RecipeRecord>> {
+        let mut recipes = vec![];
+        for (job, recipe_id) in self.recipe_ids(data)? {
+            let recipe = data.recipes()?.link(
+                data.recipe_lookups()?,
+                self.id,
+                &job.abbreviation,
+                recipe_id,
+            )?;
+            recipes.push(recipe);
+        }
+        Ok(recipes)
+    }
+}
+
+/// Reads the columns that sheets like RecipeLookup and CraftAction have for each crafting
+/// job, which are named after the job's English abbreviation. Jobs whose column is `0` are
+/// left out.
+fn job_columns<'a, S: Sheet>(
+    jobs: &'a CraftingJobs,
+    sheet: &SheetTable<S>,
+    id: u32,
+    columns: &HashMap<String, String>,
+) -> Result<Vec<(&'a ClassJobRecord, u32)>> {
+    let mut ids = vec![];
+    for job in jobs {
+        let value = columns
+            .get(&job.abbreviation)
+            .ok_or_else(|| Error::MissingColumn {
+                file: sheet.path().to_path_buf(),
+                column: job.abbreviation.clone(),
+            })?;
+        let job_id: u32 = value.trim().parse().map_err(|e| {
+            Error::bad_row(
+                sheet.path(),
+                id,
+                format!("column `{}`: {e}", job.abbreviation),
+            )
+        })?;
+        if job_id != 0 {
+            ids.push((job, job_id));
+        }
+    }
+    Ok(ids)
+}
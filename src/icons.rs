@@ -1,31 +1,113 @@
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
-use crate::models::{ActionRecord, CraftActionRecord, StatusRecord};
-use crate::utils::{read_csv_data, write_json_file};
+use crate::error::{Error, Result};
+use crate::game_data::{GameData, SheetTable};
+use crate::models::{ActionRecord, ClassJobRecord, CraftActionRecord, StatusRecord};
+use crate::utils::write_json_file;
+use crate::validate::SheetSchema;
 
 struct IconData {
     pub name: String,
-    pub job: Option<String>,
+    /// The crafting job that the icon is specific to, by ClassJob id
+    pub job: Option<u32>,
 }
 
-pub fn build_icons(action_icons_path: &Path) -> (HashMap<u32, String>, HashMap<u32, String>) {
-    let icons_dir = Path::new("output/icon");
-    if icons_dir.exists() {
-        fs::remove_dir_all("output/icon").unwrap();
-    }
-    fs::create_dir_all("output/icon/action").unwrap();
-    fs::create_dir_all("output/icon/status").unwrap();
+pub fn sheets() -> Vec<SheetSchema> {
+    vec![
+        SheetSchema::of::<ActionRecord>(),
+        SheetSchema::of::<ClassJobRecord>(),
+        SheetSchema::of::<CraftActionRecord>(),
+        SheetSchema::of::<StatusRecord>(),
+    ]
+}
 
+/// Collects the names of every player combat action and crafting action that has a job,
+/// keyed by action id. These are the actions that get icons and translations.
+pub fn build_action_names(data: &GameData) -> Result<(HashMap<u32, String>, HashMap<u32, String>)> {
     let mut relevant_actions = HashMap::new();
+    for action in data.actions()? {
+        if is_relevant_action(action) {
+            relevant_actions.insert(action.id, action.name.clone());
+        }
+    }
+
     let mut relevant_craft_actions = HashMap::new();
+    for craft_action in data.craft_actions()? {
+        if is_relevant_craft_action(craft_action) {
+            relevant_craft_actions.insert(craft_action.id, craft_action.name.clone());
+        }
+    }
+
+    Ok((relevant_actions, relevant_craft_actions))
+}
+
+/// Collects the names of every crafting status, keyed by status id
+pub fn build_status_names(data: &GameData) -> Result<HashMap<u32, String>> {
+    let mut relevant_statuses = HashMap::new();
+    for status in data.statuses()? {
+        if is_relevant_status(status) {
+            relevant_statuses.insert(status.id, status.name.clone());
+        }
+    }
+
+    Ok(relevant_statuses)
+}
+
+/// The names a status's icons are published under. Statuses with stacks have one icon per
+/// stack count, e.g. `Inner Quiet-1` to `Inner Quiet-10`.
+pub fn status_icon_names(name: &str, max_stacks: u32) -> Vec<String> {
+    if max_stacks == 0 {
+        vec![String::from(name)]
+    } else {
+        (1..=max_stacks)
+            .map(|stacks| format!("{name}-{stacks}"))
+            .collect()
+    }
+}
+
+fn is_relevant_action(action: &ActionRecord) -> bool {
+    action.action_category == 7 && action.class_job > 0 && action.is_player_action
+}
+
+fn is_relevant_craft_action(craft_action: &CraftActionRecord) -> bool {
+    craft_action.is_used()
+}
+
+fn is_relevant_status(status: &StatusRecord) -> bool {
+    status.category == 33
+}
+
+/// An extracted icon file, and the name it's published under
+#[derive(Debug, Clone)]
+pub struct IconFile {
+    pub name: String,
+    pub source: PathBuf,
+}
+
+impl IconFile {
+    fn filename(&self) -> String {
+        match self.source.extension() {
+            Some(ext) => format!("{}.{}", self.name, ext.to_string_lossy()),
+            None => self.name.clone(),
+        }
+    }
+}
+
+pub struct Icons {
+    pub actions: Vec<IconFile>,
+    pub statuses: Vec<IconFile>,
+}
+
+pub fn build_icons(data: &GameData, action_icons_path: &Path) -> Result<Icons> {
+    let class_jobs = data.class_jobs()?;
 
     // read in action icons
     let mut icons_by_id: HashMap<u32, IconData> = HashMap::new();
 
-    let mut record_icon = |icon_id: u32, name: String, job: Option<String>| {
+    let mut record_icon = |icon_id: u32, name: String, job: Option<u32>| {
         icons_by_id
             .entry(icon_id)
             .and_modify(|icon_data| {
@@ -37,61 +119,63 @@ pub fn build_icons(action_icons_path: &Path) -> (HashMap<u32, String>, HashMap<u
             .or_insert(IconData { name, job });
     };
 
-    for action in read_csv_data::<ActionRecord>("data/Action.csv") {
-        if action.action_category != 7 || action.class_job <= 0 || !action.is_player_action {
+    for action in data.actions()? {
+        if !is_relevant_action(action) {
             continue;
         }
-        relevant_actions.insert(action.id, action.name.clone());
-        record_icon(action.icon, action.name, job_string(action.class_job));
+        record_icon(
+            action.icon,
+            action.name.clone(),
+            crafting_job(class_jobs, action.class_job),
+        );
     }
 
-    for craft_action in read_csv_data::<CraftActionRecord>("data/CraftAction.csv") {
-        if craft_action.class_job <= 0 {
+    for craft_action in data.craft_actions()? {
+        if !is_relevant_craft_action(craft_action) {
             continue;
         }
-        relevant_craft_actions.insert(craft_action.id, craft_action.name.clone());
         record_icon(
             craft_action.icon,
-            craft_action.name,
-            job_string(craft_action.class_job),
+            craft_action.name.clone(),
+            crafting_job(class_jobs, craft_action.class_job),
         );
     }
 
     // read in status icons
     let mut statuses_by_id: HashMap<u32, String> = HashMap::new();
 
-    for status in read_csv_data::<StatusRecord>("data/Status.csv") {
-        if status.category != 33 {
+    for status in data.statuses()? {
+        if !is_relevant_status(status) {
             continue;
         }
 
-        if status.max_stacks == 0 {
-            statuses_by_id.insert(status.icon, status.name);
-        } else {
-            for stacks in 0..status.max_stacks {
-                let icon_id = status.icon + stacks;
-                let name = format!("{}-{}", status.name, stacks + 1);
-                statuses_by_id.insert(icon_id, name);
-            }
+        let names = status_icon_names(&status.name, status.max_stacks);
+        for (icon_id, name) in (status.icon..).zip(names) {
+            statuses_by_id.insert(icon_id, name);
         }
     }
 
-    let mut action_output: Vec<String> = vec![];
-    let mut status_output: Vec<String> = vec![];
+    let mut action_output: Vec<IconFile> = vec![];
+    let mut status_output: Vec<IconFile> = vec![];
 
     // iterate through icon files and match them up with action data from above
     let mut min_icon_id: u32 = 999_999;
     let mut max_icon_id: u32 = 0;
     for entry in WalkDir::new(action_icons_path) {
-        let entry = entry.unwrap();
+        let entry = entry.map_err(|e| Error::io(action_icons_path, e))?;
 
-        if !entry.metadata().unwrap().is_file() {
+        if !entry.file_type().is_file() {
             continue;
         }
 
         // 000000.png
         let filename = entry.file_name().to_string_lossy();
-        let icon_id = if let Ok(icon_id) = filename.split('.').next().unwrap().parse::<u32>() {
+        let icon_id = if let Ok(icon_id) = filename
+            .split('.')
+            .next()
+            .unwrap_or_default()
+            .parse::<u32>()
+        {
             icon_id
         } else {
             println!(
@@ -101,34 +185,33 @@ pub fn build_icons(action_icons_path: &Path) -> (HashMap<u32, String>, HashMap<u
             continue;
         };
 
-        // copy action icons
+        // match action icons
         if let Some(icon_data) = icons_by_id.get(&icon_id) {
-            let ext = entry.path().extension().unwrap().to_string_lossy();
             // some icons are class-specific, others aren't
-            let action_name = if let Some(job) = &icon_data.job {
-                format!("{}-{}", icon_data.name, job)
+            let abbreviation = icon_data
+                .job
+                .and_then(|job| class_jobs.get(job))
+                .map(|job| &job.abbreviation);
+            let action_name = if let Some(abbreviation) = abbreviation {
+                format!("{}-{}", icon_data.name, abbreviation)
             } else {
                 icon_data.name.clone()
             };
-            let filename = format!("{action_name}.{ext}");
 
-            fs::copy(entry.path(), format!("output/icon/action/{filename}"))
-                .unwrap_or_else(|_| panic!("error copying {:?}", entry.path()));
-
-            action_output.push(action_name);
+            action_output.push(IconFile {
+                name: action_name,
+                source: entry.path().to_path_buf(),
+            });
             min_icon_id = min_icon_id.min(icon_id);
             max_icon_id = max_icon_id.max(icon_id);
         }
 
-        // copy status icons
+        // match status icons
         if let Some(status_name) = statuses_by_id.get(&icon_id) {
-            let ext = entry.path().extension().unwrap().to_string_lossy();
-            let filename = format!("{status_name}.{ext}");
-
-            fs::copy(entry.path(), format!("output/icon/status/{filename}"))
-                .unwrap_or_else(|_| panic!("error copying {:?}", entry.path()));
-
-            status_output.push(String::from(status_name));
+            status_output.push(IconFile {
+                name: status_name.clone(),
+                source: entry.path().to_path_buf(),
+            });
             min_icon_id = min_icon_id.min(icon_id);
             max_icon_id = max_icon_id.max(icon_id);
         }
@@ -141,25 +224,47 @@ pub fn build_icons(action_icons_path: &Path) -> (HashMap<u32, String>, HashMap<u
         max_icon_id
     );
 
-    action_output.sort();
-    status_output.sort();
-    write_json_file(&action_output, "output/actions.json");
-    write_json_file(&status_output, "output/statuses.json");
+    action_output.sort_by(|a, b| a.name.cmp(&b.name));
+    status_output.sort_by(|a, b| a.name.cmp(&b.name));
 
-    (relevant_actions, relevant_craft_actions)
+    Ok(Icons {
+        actions: action_output,
+        statuses: status_output,
+    })
 }
 
-fn job_string(class_job: i32) -> Option<String> {
-    match class_job {
-        8 => Some("CRP"),
-        9 => Some("BSM"),
-        10 => Some("ARM"),
-        11 => Some("GSM"),
-        12 => Some("LTW"),
-        13 => Some("WVR"),
-        14 => Some("ALC"),
-        15 => Some("CUL"),
-        _ => None,
+/// Copies icons into `output_dir/icon`, replacing any previous icons, and writes
+/// actions.json and statuses.json with the published icon names
+pub fn write_icons(icons: &Icons, output_dir: &Path) -> Result<()> {
+    let icons_dir = output_dir.join("icon");
+    if icons_dir.exists() {
+        fs::remove_dir_all(&icons_dir).map_err(|e| Error::io(&icons_dir, e))?;
+    }
+
+    for (subdir, icon_files, list_filename) in [
+        ("action", &icons.actions, "actions.json"),
+        ("status", &icons.statuses, "statuses.json"),
+    ] {
+        let subdir = icons_dir.join(subdir);
+        fs::create_dir_all(&subdir).map_err(|e| Error::io(&subdir, e))?;
+
+        for icon_file in icon_files {
+            fs::copy(&icon_file.source, subdir.join(icon_file.filename()))
+                .map_err(|e| Error::io(&icon_file.source, e))?;
+        }
+
+        let names: Vec<&str> = icon_files.iter().map(|icon| icon.name.as_str()).collect();
+        write_json_file(&names, output_dir.join(list_filename))?;
     }
-    .map(String::from)
+
+    Ok(())
+}
+
+/// The class job's id if it's a crafting job
+fn crafting_job(class_jobs: &SheetTable<ClassJobRecord>, class_job: i32) -> Option<u32> {
+    let id = u32::try_from(class_job).ok()?;
+    class_jobs
+        .get(id)
+        .filter(|job| job.is_crafter())
+        .map(|job| job.id)
 }
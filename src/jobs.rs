@@ -0,0 +1,83 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::error::Result;
+use crate::game_data::GameData;
+use crate::models::{ClassJobRecord, Sheet};
+use crate::translated_items::language_tag;
+use crate::utils::write_json_file;
+use crate::validate::SheetSchema;
+
+/// `languages` are the ones with a ClassJob sheet, from [`languages_with_job_names`]
+pub fn sheets(languages: &[String]) -> Vec<SheetSchema> {
+    let mut sheets = vec![SheetSchema::of::<ClassJobRecord>()];
+    for language in languages {
+        sheets.push(SheetSchema::localized::<ClassJobRecord>(language));
+    }
+    sheets
+}
+
+/// The languages whose data subdirectory has a ClassJob sheet. The others keep the English
+/// job names.
+pub fn languages_with_job_names(data_dir: &Path, languages: &[String]) -> Vec<String> {
+    let file = format!("{}.csv", ClassJobRecord::NAME);
+    languages
+        .iter()
+        .filter(|language| data_dir.join(language).join(&file).is_file())
+        .cloned()
+        .collect()
+}
+
+/// A crafting job, with its name and abbreviation in every language
+#[derive(Debug, Clone, Serialize)]
+pub struct JobOutput {
+    /// ClassJob id, which recipes and the crafting action table refer to jobs by
+    pub id: u32,
+    /// English abbreviation, as used in icon names
+    pub abbreviation: String,
+    /// Names by language tag, including `en`
+    pub names: BTreeMap<String, String>,
+    /// Abbreviations by language tag, including `en`
+    pub abbreviations: BTreeMap<String, String>,
+}
+
+/// Builds the crafting jobs in game order. Languages without a name for a job, or without a
+/// ClassJob sheet, are left out.
+pub fn build_jobs(data: &GameData) -> Result<Vec<JobOutput>> {
+    let mut jobs = vec![];
+    for job in data.crafting_jobs()? {
+        let mut names = BTreeMap::from([(String::from("en"), job.name.clone())]);
+        let mut abbreviations = BTreeMap::from([(String::from("en"), job.abbreviation.clone())]);
+
+        for (language, localized) in data.languages() {
+            let Some(class_jobs) = localized.optional_class_jobs()? else {
+                continue;
+            };
+            let Some(localized_job) = class_jobs.get(job.id) else {
+                continue;
+            };
+            let tag = String::from(language_tag(language));
+            if !localized_job.name.is_empty() {
+                names.insert(tag.clone(), localized_job.name.clone());
+            }
+            if !localized_job.abbreviation.is_empty() {
+                abbreviations.insert(tag, localized_job.abbreviation.clone());
+            }
+        }
+
+        jobs.push(JobOutput {
+            id: job.id,
+            abbreviation: job.abbreviation.clone(),
+            names,
+            abbreviations,
+        });
+    }
+
+    Ok(jobs)
+}
+
+/// Writes `jobs.json`
+pub fn write_jobs(jobs: &[JobOutput], output_dir: &Path) -> Result<()> {
+    write_json_file(&jobs, output_dir.join("jobs.json"))
+}
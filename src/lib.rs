@@ -1,6 +1,21 @@
+pub mod conditions;
 pub mod consumables;
+pub mod craft_actions;
+pub mod descriptions;
+pub mod error;
+pub mod formulas;
+pub mod game_data;
 pub mod icons;
-mod models;
+pub mod jobs;
+pub mod lookup;
+pub mod markup;
+pub mod models;
 pub mod recipes;
+mod row;
+pub mod search;
+pub mod simulator;
 pub mod translated_items;
 mod utils;
+pub mod validate;
+
+pub use error::{Error, Result};
@@ -0,0 +1,122 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::error::Result;
+use crate::game_data::GameData;
+use crate::icons::{build_action_names, build_status_names};
+use crate::translated_items::{language_tag, ItemTranslations};
+use crate::utils::write_json_file;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LookupKind {
+    Item,
+    Action,
+    /// Identified by the first crafting job's id for the action
+    CraftAction,
+    Status,
+}
+
+/// What a localized name refers to
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct LookupEntry {
+    pub kind: LookupKind,
+    pub id: u32,
+    pub english_name: String,
+}
+
+/// Normalized localized names mapped to what they refer to, keyed by language
+pub type Lookups = BTreeMap<String, BTreeMap<String, Vec<LookupEntry>>>;
+
+/// Normalizes a name the way search input is normalized before a lookup: NFKC, which folds
+/// full-width letters and digits to half-width and half-width katakana to full-width, then
+/// lowercase, without soft hyphens and with whitespace collapsed
+pub fn normalize_name(name: &str) -> String {
+    name.nfkc()
+        .filter(|&c| c != '\u{00AD}')
+        .collect::<String>()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Builds the lookups from the localized sheets, so that names a language has no
+/// translation for aren't looked up, even if the item translations fall back to English.
+/// `items` gives the items to include, and their English names.
+pub fn build_lookups(data: &GameData, items: &ItemTranslations) -> Result<Lookups> {
+    let (english_actions, english_craft_actions) = build_action_names(data)?;
+    let english_statuses = build_status_names(data)?;
+
+    let mut lookups = Lookups::new();
+    for (language, localized) in data.languages() {
+        let mut names: Vec<(&str, LookupEntry)> = vec![];
+        let entry = |kind, id, english_name: &str| LookupEntry {
+            kind,
+            id,
+            english_name: String::from(english_name),
+        };
+
+        let localized_items = localized.items()?;
+        for (id, item) in items {
+            if let Some(localized_item) = localized_items.get(*id) {
+                let item_entry = entry(LookupKind::Item, *id, &item.english);
+                names.push((&localized_item.name, item_entry));
+            }
+        }
+
+        let actions = localized.actions()?;
+        for (id, english_name) in &english_actions {
+            if let Some(action) = actions.get(*id) {
+                names.push((&action.name, entry(LookupKind::Action, *id, english_name)));
+            }
+        }
+
+        let craft_actions = localized.craft_actions()?;
+        for (id, english_name) in &english_craft_actions {
+            let (Some(craft_action), Some(english_craft_action)) =
+                (craft_actions.get(*id), data.craft_actions()?.get(*id))
+            else {
+                continue;
+            };
+            let action_id = english_craft_action.action_id(data)?;
+            let craft_action_entry = entry(LookupKind::CraftAction, action_id, english_name);
+            names.push((&craft_action.name, craft_action_entry));
+        }
+
+        let statuses = localized.statuses()?;
+        for (id, english_name) in &english_statuses {
+            if let Some(status) = statuses.get(*id) {
+                names.push((&status.name, entry(LookupKind::Status, *id, english_name)));
+            }
+        }
+
+        let mut lookup: BTreeMap<String, Vec<LookupEntry>> = BTreeMap::new();
+        for (name, entry) in names {
+            let name = normalize_name(name);
+            if !name.is_empty() {
+                lookup.entry(name).or_default().push(entry);
+            }
+        }
+        for entries in lookup.values_mut() {
+            entries.sort();
+            entries.dedup();
+        }
+
+        lookups.insert(String::from(language), lookup);
+    }
+
+    Ok(lookups)
+}
+
+/// Writes one `lookup_{language tag}.json` file per language
+pub fn write_lookups(lookups: &Lookups, output_dir: &Path) -> Result<()> {
+    for (language, lookup) in lookups {
+        let tag = language_tag(language);
+        write_json_file(lookup, output_dir.join(format!("lookup_{tag}.json")))?;
+    }
+
+    Ok(())
+}
@@ -5,32 +5,386 @@
     clippy::cast_sign_loss
 )]
 
+use clap::{CommandFactory, Parser, Subcommand};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
 use craftingway_data::{
-    consumables::build_consumables,
-    icons::build_icons,
-    recipes::build_recipes,
-    translated_items::{build_translated_actions, build_translated_items},
+    consumables::{self, build_consumables, write_consumables},
+    craft_actions::{self, build_craft_actions, write_craft_actions},
+    descriptions::{self, build_descriptions, write_descriptions},
+    error::{Error, Result},
+    game_data::GameData,
+    icons::{self, build_action_names, build_icons, build_status_names, write_icons},
+    jobs::{self, build_jobs, write_jobs},
+    lookup::{build_lookups, write_lookups},
+    markup::Format,
+    recipes::{
+        self, build_localized_recipes, build_recipes, write_localized_recipes, write_recipes,
+        RecipeOutput, Recipes,
+    },
+    search::{build_search_index, write_search_index},
+    translated_items::{
+        self, build_translated_actions, build_translated_items, build_translated_statuses,
+        detect_languages, item_name_collisions, legacy_item_translations, write_coverage,
+        write_item_translations, write_translations, Coverage, MissingPolicy,
+    },
+    validate::{validate_sheets, SheetSchema},
 };
 
-fn main() {
+#[derive(Parser)]
+#[command(about = "Generates Craftingway data files from exported game sheets")]
+struct Cli {
+    /// Directory containing the exported game data CSVs
+    #[arg(long, global = true, default_value = "data")]
+    data_dir: PathBuf,
+
+    /// Directory that generated files are written to
+    #[arg(long, global = true, default_value = "output")]
+    output_dir: PathBuf,
+
+    /// Directory containing the extracted action and status icons
+    #[arg(long, global = true)]
+    icons_dir: Option<PathBuf>,
+
+    /// List every recipe that was merged across jobs, not just items whose recipes differ
+    #[arg(long, global = true)]
+    merges: bool,
+
+    /// Languages to translate into, as data directory subdirectories, e.g. `jpn,deu`.
+    /// Defaults to every subdirectory that has the localized sheets.
+    #[arg(long, global = true, value_delimiter = ',')]
+    languages: Vec<String>,
+
+    /// What to do with names that a language has no translation for
+    #[arg(long, global = true, value_enum, default_value_t = MissingPolicy::Omit)]
+    missing_translations: MissingPolicy,
+
+    /// Also write item translations keyed by English name, one file per language
+    #[arg(long, global = true)]
+    legacy_translations: bool,
+
+    /// Format of the action and status description files. Only descriptions use it: names in
+    /// every output stay plain text, since they're also used as keys and for search.
+    #[arg(long, global = true, value_enum, default_value_t = Format::Plain)]
+    text_format: Format,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Build recipes.json
+    Recipes,
+    /// Build meals.json and potions.json
+    Consumables,
+    /// Build the crafting action table
+    Actions,
+    /// Copy action and status icons, and build actions.json and statuses.json
+    Icons,
+    /// Build item, action and status translation files
+    Translations,
+    /// Build crafting action and status descriptions in every language
+    Descriptions,
+    /// Build jobs.json with crafting job names in every language
+    Jobs,
+    /// Run every stage
+    All,
+    /// Check every sheet's columns against the records that read them
+    Validate {
+        /// List unused columns by name
+        #[arg(long)]
+        unused: bool,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(&cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: &Cli) -> Result<()> {
+    std::fs::create_dir_all(&cli.output_dir).map_err(|source| Error::Io {
+        path: cli.output_dir.clone(),
+        source,
+    })?;
+
+    let languages = if cli.languages.is_empty() {
+        let detected = detect_languages(&cli.data_dir)?;
+        for skipped in &detected.skipped {
+            eprintln!(
+                "warning: not using data subdirectory {:?} as a language, since it has no {}",
+                skipped.name,
+                skipped.missing.join(", ")
+            );
+        }
+        detected.languages
+    } else {
+        cli.languages.clone()
+    };
+    let job_languages = jobs::languages_with_job_names(&cli.data_dir, &languages);
+    for language in languages
+        .iter()
+        .filter(|language| !job_languages.contains(language))
+    {
+        eprintln!(
+            "warning: data subdirectory {language:?} has no ClassJob.csv, so its job names stay in English"
+        );
+    }
+
+    let sheets = match cli.command {
+        Command::Recipes => recipes::sheets(),
+        Command::Consumables => consumables::sheets(),
+        Command::Actions => craft_actions::sheets(),
+        Command::Icons => icons::sheets(),
+        Command::Translations => [
+            recipes::sheets(),
+            consumables::sheets(),
+            translated_items::sheets(&languages),
+            jobs::sheets(&job_languages),
+        ]
+        .concat(),
+        Command::Descriptions => descriptions::sheets(&languages),
+        Command::Jobs => jobs::sheets(&job_languages),
+        Command::All | Command::Validate { .. } => [
+            recipes::sheets(),
+            consumables::sheets(),
+            craft_actions::sheets(),
+            icons::sheets(),
+            translated_items::sheets(&languages),
+            descriptions::sheets(&languages),
+            jobs::sheets(&job_languages),
+        ]
+        .concat(),
+    };
+
+    if let Command::Validate { unused } = cli.command {
+        return validate(cli, &sheets, unused);
+    }
+    check_sheets(cli, &sheets)?;
+
+    // sheets are read once and shared between stages
+    let data = GameData::new(&cli.data_dir).with_languages(languages.iter().map(String::as_str));
+
+    match cli.command {
+        Command::Validate { .. } => unreachable!(),
+        Command::Recipes => {
+            recipes(cli, &data)?;
+        }
+        Command::Consumables => {
+            consumables(cli, &data)?;
+        }
+        Command::Actions => {
+            craft_actions(cli, &data)?;
+        }
+        Command::Icons => {
+            icons(cli, &data, require_icons_dir(cli))?;
+        }
+        Command::Translations => {
+            // the set of translated items is derived from recipes and consumables
+            let (recipes, item_names) = items(cli, &data)?;
+            translations(cli, &data, &recipes, &item_names)?;
+        }
+        Command::Descriptions => {
+            descriptions(cli, &data)?;
+        }
+        Command::Jobs => {
+            jobs(cli, &data)?;
+        }
+        Command::All => {
+            let icons_dir = require_icons_dir(cli);
+            let (recipes, item_names) = items(cli, &data)?;
+            craft_actions(cli, &data)?;
+            icons(cli, &data, icons_dir)?;
+            translations(cli, &data, &recipes, &item_names)?;
+            descriptions(cli, &data)?;
+            jobs(cli, &data)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn validate(cli: &Cli, sheets: &[SheetSchema], unused: bool) -> Result<()> {
+    let mut invalid_files = vec![];
+    for report in validate_sheets(&cli.data_dir, sheets) {
+        if unused {
+            println!("{report:#}");
+        } else {
+            println!("{report}");
+        }
+        if !report.is_valid() {
+            invalid_files.push(report.file);
+        }
+    }
+
+    if invalid_files.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::InvalidSheets {
+            files: invalid_files,
+        })
+    }
+}
+
+/// Validates sheets before a stage reads them, only reporting the ones with problems
+fn check_sheets(cli: &Cli, sheets: &[SheetSchema]) -> Result<()> {
+    let mut invalid_files = vec![];
+    for report in validate_sheets(&cli.data_dir, sheets) {
+        if !report.is_valid() {
+            eprintln!("{report}");
+            invalid_files.push(report.file);
+        }
+    }
+
+    if invalid_files.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::InvalidSheets {
+            files: invalid_files,
+        })
+    }
+}
+
+fn require_icons_dir(cli: &Cli) -> &Path {
+    let Some(icons_dir) = cli.icons_dir.as_deref() else {
+        Cli::command()
+            .error(
+                clap::error::ErrorKind::MissingRequiredArgument,
+                "--icons-dir is required to build icons",
+            )
+            .exit();
+    };
+
+    if !icons_dir.exists() {
+        Cli::command()
+            .error(
+                clap::error::ErrorKind::ValueValidation,
+                format!("Invalid path for action icons: {}", icons_dir.display()),
+            )
+            .exit();
+    }
+
+    icons_dir
+}
+
+fn recipes(cli: &Cli, data: &GameData) -> Result<Recipes> {
     println!("Building recipes.json...");
-    let mut item_names = build_recipes();
+    let recipes = build_recipes(data)?;
+    if cli.merges {
+        println!("{:#}", recipes.report);
+    } else {
+        println!("{}", recipes.report);
+    }
+    write_recipes(&recipes.recipes, &cli.output_dir)?;
+    Ok(recipes)
+}
 
+fn consumables(cli: &Cli, data: &GameData) -> Result<HashMap<u32, String>> {
     println!("Building meals.json and potions.json...");
-    let consumable_item_names = build_consumables();
-    item_names.extend(consumable_item_names);
+    let consumables = build_consumables(data)?;
+    write_consumables(&consumables, &cli.output_dir)?;
+    Ok(consumables.item_names)
+}
 
-    println!("Building item translation files...");
-    build_translated_items(item_names);
+fn craft_actions(cli: &Cli, data: &GameData) -> Result<()> {
+    println!("Building actions_data.json...");
+    let craft_actions = build_craft_actions(data)?;
+    write_craft_actions(&craft_actions, &cli.output_dir)
+}
 
-    let args: Vec<String> = std::env::args().collect();
-    assert!(args.len() == 2, "Path for action icons wasn't provided");
-    let action_icons_path = std::path::Path::new(&args[1]);
-    assert!(action_icons_path.exists(), "Invalid path for action icons");
+/// Builds recipes and consumables, and returns the recipes along with the English names of
+/// every item they use
+fn items(cli: &Cli, data: &GameData) -> Result<(Vec<RecipeOutput>, HashMap<u32, String>)> {
+    let Recipes {
+        recipes,
+        mut item_names,
+        ..
+    } = recipes(cli, data)?;
+    item_names.extend(consumables(cli, data)?);
+    Ok((recipes, item_names))
+}
 
+fn icons(cli: &Cli, data: &GameData, icons_dir: &Path) -> Result<()> {
     println!("Finding icons...");
-    let (action_names, craft_action_names) = build_icons(action_icons_path);
+    let icons = build_icons(data, icons_dir)?;
+    write_icons(&icons, &cli.output_dir)
+}
+
+fn translations(
+    cli: &Cli,
+    data: &GameData,
+    recipes: &[RecipeOutput],
+    item_names: &HashMap<u32, String>,
+) -> Result<()> {
+    let mut coverage = Coverage::new();
+
+    println!("Building item_translations.json...");
+    let item_translations =
+        build_translated_items(data, item_names, cli.missing_translations, &mut coverage)?;
+    write_item_translations(&item_translations, &cli.output_dir)?;
+
+    for (name, ids) in item_name_collisions(&item_translations) {
+        eprintln!(
+            "warning: items {ids:?} are all named {name:?} in English, but are translated \
+             differently"
+        );
+    }
+    println!("Building localized recipe files...");
+    let localized_recipes = build_localized_recipes(data, recipes, &item_translations)?;
+    write_localized_recipes(&localized_recipes, &cli.output_dir)?;
+
+    println!("Building lookup files...");
+    let lookups = build_lookups(data, &item_translations)?;
+    write_lookups(&lookups, &cli.output_dir)?;
+
+    println!("Building search_index.json...");
+    let search_index = build_search_index(recipes, &item_translations);
+    write_search_index(&search_index, &cli.output_dir)?;
+
+    if cli.legacy_translations {
+        println!("Building legacy item translation files...");
+        let legacy_translations = legacy_item_translations(&item_translations);
+        write_translations(&legacy_translations, "items", &cli.output_dir)?;
+    }
 
     println!("Building action translation files...");
-    build_translated_actions(action_names, craft_action_names);
+    let (action_names, craft_action_names) = build_action_names(data)?;
+    let action_translations = build_translated_actions(
+        data,
+        &action_names,
+        &craft_action_names,
+        cli.missing_translations,
+        &mut coverage,
+    )?;
+    write_translations(&action_translations, "actions", &cli.output_dir)?;
+
+    println!("Building status translation files...");
+    let status_names = build_status_names(data)?;
+    let status_translations =
+        build_translated_statuses(data, &status_names, cli.missing_translations, &mut coverage)?;
+    write_translations(&status_translations, "statuses", &cli.output_dir)?;
+
+    write_coverage(&coverage, &cli.output_dir)
+}
+
+fn descriptions(cli: &Cli, data: &GameData) -> Result<()> {
+    println!("Building description files...");
+    let descriptions = build_descriptions(data, cli.text_format)?;
+    write_descriptions(&descriptions, &cli.output_dir)
+}
+
+fn jobs(cli: &Cli, data: &GameData) -> Result<()> {
+    println!("Building jobs.json...");
+    let jobs = build_jobs(data)?;
+    write_jobs(&jobs, &cli.output_dir)
 }
@@ -0,0 +1,661 @@
+//! Parser and evaluator for the markup in game text, e.g.
+//! `<If(GreaterThanOrEqualTo(PlayerParameter(69),31))>120<Else/>100</If>`.
+
+use serde::{de, Deserialize, Deserializer};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+mod format;
+
+pub use format::Format;
+
+/// The player's job id
+pub const JOB_PARAMETER: u32 = 68;
+/// The player's level in their current job
+pub const LEVEL_PARAMETER: u32 = 69;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("markup at byte {position}: {message}")]
+pub struct ParseError {
+    pub position: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Number(i64),
+    PlayerParameter(u32),
+    Equal(Box<Expr>, Box<Expr>),
+    NotEqual(Box<Expr>, Box<Expr>),
+    GreaterThan(Box<Expr>, Box<Expr>),
+    GreaterThanOrEqualTo(Box<Expr>, Box<Expr>),
+    LessThan(Box<Expr>, Box<Expr>),
+    LessThanOrEqualTo(Box<Expr>, Box<Expr>),
+    /// An expression this parser doesn't know, like `ObjectParameter(1)`, kept as written.
+    /// It evaluates to 0, like a parameter that isn't set.
+    Unknown(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node {
+    Text(String),
+    If {
+        condition: Expr,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+    Value(Expr),
+    /// Text color, as an encoded color code. See [`color_index`].
+    Foreground(String),
+    /// Text outline color, encoded like [`Node::Foreground`]
+    Glow(String),
+    /// Starts emphasized text if `true`, ends it if `false`
+    Emphasis(bool),
+    SoftHyphen,
+    NonBreakingSpace,
+    Indent,
+    /// Any other tag, kept as written
+    Tag(String),
+}
+
+/// The UIColor row that a color code refers to, or `None` if it resets the color. Codes are
+/// encoded integers: one byte holding the value plus one, or a marker byte like `F2` followed
+/// by the value's bytes.
+pub fn color_index(code: &str) -> Option<u32> {
+    match code.len() {
+        2 => u32::from_str_radix(code, 16).ok()?.checked_sub(1),
+        _ if code.starts_with('F') => u32::from_str_radix(code.get(2..)?, 16).ok(),
+        _ => None,
+    }
+    .filter(|&index| index > 0)
+}
+
+/// Values for `PlayerParameter(n)`. Parameters that aren't set are 0.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Parameters(HashMap<u32, i64>);
+
+impl Parameters {
+    pub fn new() -> Self {
+        Parameters::default()
+    }
+
+    /// Parameters for a player of the given job and level
+    pub fn for_job(job_id: u32, level: u32) -> Self {
+        Parameters::new()
+            .with(JOB_PARAMETER, i64::from(job_id))
+            .with(LEVEL_PARAMETER, i64::from(level))
+    }
+
+    pub fn with(mut self, parameter: u32, value: i64) -> Self {
+        self.0.insert(parameter, value);
+        self
+    }
+
+    pub fn get(&self, parameter: u32) -> i64 {
+        self.0.get(&parameter).copied().unwrap_or(0)
+    }
+}
+
+impl Expr {
+    pub fn evaluate(&self, parameters: &Parameters) -> i64 {
+        let compare = |a: &Expr, b: &Expr, op: fn(&i64, &i64) -> bool| {
+            i64::from(op(&a.evaluate(parameters), &b.evaluate(parameters)))
+        };
+        match self {
+            Expr::Number(n) => *n,
+            Expr::PlayerParameter(parameter) => parameters.get(*parameter),
+            Expr::Equal(a, b) => compare(a, b, i64::eq),
+            Expr::NotEqual(a, b) => compare(a, b, i64::ne),
+            Expr::GreaterThan(a, b) => compare(a, b, i64::gt),
+            Expr::GreaterThanOrEqualTo(a, b) => compare(a, b, i64::ge),
+            Expr::LessThan(a, b) => compare(a, b, i64::lt),
+            Expr::LessThanOrEqualTo(a, b) => compare(a, b, i64::le),
+            Expr::Unknown(_) => 0,
+        }
+    }
+
+    fn visit(&self, f: &mut impl FnMut(&Expr)) {
+        f(self);
+        match self {
+            Expr::Number(_) | Expr::PlayerParameter(_) | Expr::Unknown(_) => {}
+            Expr::Equal(a, b)
+            | Expr::NotEqual(a, b)
+            | Expr::GreaterThan(a, b)
+            | Expr::GreaterThanOrEqualTo(a, b)
+            | Expr::LessThan(a, b)
+            | Expr::LessThanOrEqualTo(a, b) => {
+                a.visit(f);
+                b.visit(f);
+            }
+        }
+    }
+}
+
+/// A parsed piece of game text
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Markup(pub Vec<Node>);
+
+impl Markup {
+    pub fn parse(text: &str) -> Result<Markup, ParseError> {
+        let mut parser = Parser { text, position: 0 };
+        let (nodes, end) = parser.nodes()?;
+        match end {
+            Stop::Eof => Ok(Markup(nodes)),
+            Stop::Else | Stop::EndIf => Err(parser.error("`Else` or `/If` without `If`")),
+        }
+    }
+
+    /// Evaluates conditions and values, and picks the branches that apply
+    pub fn evaluate(&self, parameters: &Parameters) -> Vec<Node> {
+        let mut evaluated = vec![];
+        evaluate_nodes(&self.0, parameters, &mut evaluated);
+        evaluated
+    }
+
+    /// Plain text, without any formatting
+    pub fn render(&self, parameters: &Parameters) -> String {
+        self.format(parameters, Format::Plain)
+    }
+
+    /// Text in the given format, keeping whatever formatting it supports
+    pub fn format(&self, parameters: &Parameters, format: Format) -> String {
+        format::write(&self.evaluate(parameters), format)
+    }
+
+    /// Levels where a comparison of the player's level with a number changes its result,
+    /// i.e. the levels where the text may change
+    pub fn level_thresholds(&self) -> BTreeSet<u32> {
+        let mut thresholds = BTreeSet::new();
+        let mut visit = |expr: &Expr| {
+            let (Expr::Equal(a, b)
+            | Expr::NotEqual(a, b)
+            | Expr::GreaterThan(a, b)
+            | Expr::GreaterThanOrEqualTo(a, b)
+            | Expr::LessThan(a, b)
+            | Expr::LessThanOrEqualTo(a, b)) = expr
+            else {
+                return;
+            };
+            let (number, level_first) = match (&**a, &**b) {
+                (Expr::PlayerParameter(LEVEL_PARAMETER), Expr::Number(number)) => (*number, true),
+                (Expr::Number(number), Expr::PlayerParameter(LEVEL_PARAMETER)) => (*number, false),
+                _ => return,
+            };
+            // `level >= n` first holds at n, and `level > n` at n + 1
+            let next = number.saturating_add(1);
+            let levels = match (expr, level_first) {
+                (Expr::GreaterThanOrEqualTo(..) | Expr::LessThan(..), true)
+                | (Expr::LessThanOrEqualTo(..) | Expr::GreaterThan(..), false) => vec![number],
+                (Expr::GreaterThan(..) | Expr::LessThanOrEqualTo(..), true)
+                | (Expr::LessThan(..) | Expr::GreaterThanOrEqualTo(..), false) => vec![next],
+                _ => vec![number, next],
+            };
+            thresholds.extend(
+                levels
+                    .into_iter()
+                    .filter_map(|level| u32::try_from(level).ok()),
+            );
+        };
+        visit_exprs(&self.0, &mut visit);
+        thresholds
+    }
+
+    /// Text for the job at each level where it changes, starting from level 1
+    pub fn by_level(&self, job_id: u32, format: Format) -> Vec<(u32, String)> {
+        let mut levels = vec![1];
+        levels.extend(
+            self.level_thresholds()
+                .into_iter()
+                .filter(|&level| level > 1),
+        );
+
+        let mut texts: Vec<(u32, String)> = vec![];
+        for level in levels {
+            let text = self.format(&Parameters::for_job(job_id, level), format);
+            if texts.last().is_none_or(|(_, last)| *last != text) {
+                texts.push((level, text));
+            }
+        }
+        texts
+    }
+
+    /// Numbers from the plain text's `Label: value` lines, at each level where the text
+    /// changes for the job. See [`labeled_numbers`].
+    pub fn numbers_by_level(&self, job_id: u32) -> BTreeMap<u32, BTreeMap<String, u32>> {
+        self.by_level(job_id, Format::Plain)
+            .into_iter()
+            .map(|(level, text)| (level, labeled_numbers(&text)))
+            .collect()
+    }
+}
+
+/// Formats game text that doesn't depend on the player, like names
+pub fn sanitize(text: &str, format: Format) -> Result<String, ParseError> {
+    Ok(Markup::parse(text)?.format(&Parameters::new(), format))
+}
+
+/// Deserializes a sheet column of game text into plain text
+pub(crate) fn deserialize_plain<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<String, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    sanitize(&text, Format::Plain).map_err(de::Error::custom)
+}
+
+/// Deserializes a sheet column of game text as markup
+pub(crate) fn deserialize_markup<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Markup, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    Markup::parse(&text).map_err(de::Error::custom)
+}
+
+/// Numbers from `Label: 120%` lines of plain text, keyed by label
+pub fn labeled_numbers(text: &str) -> BTreeMap<String, u32> {
+    let mut numbers = BTreeMap::new();
+    for line in text.lines() {
+        let Some((label, value)) = line.split_once([':', '：']) else {
+            continue;
+        };
+        let digits: String = value
+            .trim()
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect();
+        if let Ok(number) = digits.parse() {
+            numbers.insert(String::from(label.trim()), number);
+        }
+    }
+    numbers
+}
+
+fn evaluate_nodes(nodes: &[Node], parameters: &Parameters, evaluated: &mut Vec<Node>) {
+    for node in nodes {
+        match node {
+            Node::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                let branch = if condition.evaluate(parameters) != 0 {
+                    then
+                } else {
+                    otherwise
+                };
+                evaluate_nodes(branch, parameters, evaluated);
+            }
+            Node::Value(expr) => {
+                evaluated.push(Node::Text(expr.evaluate(parameters).to_string()));
+            }
+            node => evaluated.push(node.clone()),
+        }
+    }
+}
+
+fn visit_exprs(nodes: &[Node], f: &mut impl FnMut(&Expr)) {
+    for node in nodes {
+        match node {
+            Node::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                condition.visit(f);
+                visit_exprs(then, f);
+                visit_exprs(otherwise, f);
+            }
+            Node::Value(expr) => expr.visit(f),
+            _ => {}
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, a, b) = match self {
+            Expr::Number(n) => return write!(f, "{n}"),
+            Expr::PlayerParameter(parameter) => return write!(f, "PlayerParameter({parameter})"),
+            Expr::Unknown(text) => return f.write_str(text),
+            Expr::Equal(a, b) => ("Equal", a, b),
+            Expr::NotEqual(a, b) => ("NotEqual", a, b),
+            Expr::GreaterThan(a, b) => ("GreaterThan", a, b),
+            Expr::GreaterThanOrEqualTo(a, b) => ("GreaterThanOrEqualTo", a, b),
+            Expr::LessThan(a, b) => ("LessThan", a, b),
+            Expr::LessThanOrEqualTo(a, b) => ("LessThanOrEqualTo", a, b),
+        };
+        write!(f, "{name}({a},{b})")
+    }
+}
+
+/// Writes the markup back out in the game's format
+impl fmt::Display for Markup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write_nodes(f: &mut fmt::Formatter<'_>, nodes: &[Node]) -> fmt::Result {
+            for node in nodes {
+                match node {
+                    Node::Text(text) => f.write_str(text)?,
+                    Node::If {
+                        condition,
+                        then,
+                        otherwise,
+                    } => {
+                        write!(f, "<If({condition})>")?;
+                        write_nodes(f, then)?;
+                        f.write_str("<Else/>")?;
+                        write_nodes(f, otherwise)?;
+                        f.write_str("</If>")?;
+                    }
+                    Node::Value(expr) => write!(f, "<Value>{expr}</Value>")?,
+                    Node::Foreground(code) => write!(f, "<UIForeground>{code}</UIForeground>")?,
+                    Node::Glow(code) => write!(f, "<UIGlow>{code}</UIGlow>")?,
+                    Node::Emphasis(true) => f.write_str("<Emphasis>")?,
+                    Node::Emphasis(false) => f.write_str("</Emphasis>")?,
+                    Node::SoftHyphen => f.write_str("<SoftHyphen/>")?,
+                    Node::NonBreakingSpace => f.write_str("<NonBreakingSpace/>")?,
+                    Node::Indent => f.write_str("<Indent/>")?,
+                    Node::Tag(tag) => f.write_str(tag)?,
+                }
+            }
+            Ok(())
+        }
+        write_nodes(f, &self.0)
+    }
+}
+
+/// What ended a list of nodes
+enum Stop {
+    Eof,
+    Else,
+    EndIf,
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError {
+            position: self.position,
+            message: message.into(),
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.position..]
+    }
+
+    fn nodes(&mut self) -> Result<(Vec<Node>, Stop), ParseError> {
+        let mut nodes = vec![];
+        let mut text = String::new();
+
+        loop {
+            let rest = self.rest();
+            let Some(start) = rest.find('<') else {
+                text.push_str(rest);
+                self.position = self.text.len();
+                break;
+            };
+            text.push_str(&rest[..start]);
+            self.position += start;
+
+            let rest = self.rest();
+            // a `<` that doesn't start a tag is just text
+            let Some(end) = rest.find('>') else {
+                text.push_str(rest);
+                self.position = self.text.len();
+                break;
+            };
+            let tag = &rest[..=end];
+            self.position += tag.len();
+
+            let node = match tag {
+                "<Else/>" | "</If>" => {
+                    push_text(&mut nodes, &mut text);
+                    let end = if tag == "<Else/>" {
+                        Stop::Else
+                    } else {
+                        Stop::EndIf
+                    };
+                    return Ok((nodes, end));
+                }
+                "<Emphasis>" => Node::Emphasis(true),
+                "</Emphasis>" => Node::Emphasis(false),
+                "<SoftHyphen/>" => Node::SoftHyphen,
+                "<NonBreakingSpace/>" => Node::NonBreakingSpace,
+                "<Indent/>" => Node::Indent,
+                "<UIForeground>" => Node::Foreground(self.until("</UIForeground>")?),
+                "<UIGlow>" => Node::Glow(self.until("</UIGlow>")?),
+                "<Value>" => Node::Value(parse_expr(&self.until("</Value>")?)),
+                _ if tag.starts_with("<If(") && tag.ends_with(")>") => {
+                    self.if_node(parse_expr(&tag[4..tag.len() - 2]))?
+                }
+                _ => Node::Tag(String::from(tag)),
+            };
+            push_text(&mut nodes, &mut text);
+            nodes.push(node);
+        }
+
+        push_text(&mut nodes, &mut text);
+        Ok((nodes, Stop::Eof))
+    }
+
+    fn if_node(&mut self, condition: Expr) -> Result<Node, ParseError> {
+        let (then, end) = self.nodes()?;
+        let otherwise = match end {
+            Stop::Else => match self.nodes()? {
+                (otherwise, Stop::EndIf) => otherwise,
+                _ => return Err(self.error("`If` without `/If`")),
+            },
+            Stop::EndIf => vec![],
+            Stop::Eof => return Err(self.error("`If` without `/If`")),
+        };
+        Ok(Node::If {
+            condition,
+            then,
+            otherwise,
+        })
+    }
+
+    /// Reads raw text up to a closing tag, and skips past it
+    fn until(&mut self, closing_tag: &str) -> Result<String, ParseError> {
+        let Some(end) = self.rest().find(closing_tag) else {
+            return Err(self.error(format!("missing `{closing_tag}`")));
+        };
+        let content = String::from(&self.rest()[..end]);
+        self.position += end + closing_tag.len();
+        Ok(content)
+    }
+}
+
+fn push_text(nodes: &mut Vec<Node>, text: &mut String) {
+    if !text.is_empty() {
+        nodes.push(Node::Text(std::mem::take(text)));
+    }
+}
+
+/// Parses an expression. Anything that isn't a number or a known function with the right
+/// arguments becomes [`Expr::Unknown`], so that one unusual row doesn't make a sheet unreadable.
+fn parse_expr(text: &str) -> Expr {
+    let text = text.trim();
+    if let Ok(number) = text.parse() {
+        return Expr::Number(number);
+    }
+    let unknown = || Expr::Unknown(String::from(text));
+
+    let Some((name, args)) = text.strip_suffix(')').and_then(|text| text.split_once('(')) else {
+        return unknown();
+    };
+    let args: Vec<Expr> = split_args(args).into_iter().map(parse_expr).collect();
+
+    if let ("PlayerParameter", [Expr::Number(parameter)]) = (name, args.as_slice()) {
+        return u32::try_from(*parameter).map_or_else(|_| unknown(), Expr::PlayerParameter);
+    }
+    let constructor: fn(Box<Expr>, Box<Expr>) -> Expr = match name {
+        "Equal" => Expr::Equal,
+        "NotEqual" => Expr::NotEqual,
+        "GreaterThan" => Expr::GreaterThan,
+        "GreaterThanOrEqualTo" => Expr::GreaterThanOrEqualTo,
+        "LessThan" => Expr::LessThan,
+        "LessThanOrEqualTo" => Expr::LessThanOrEqualTo,
+        _ => return unknown(),
+    };
+    match <[Expr; 2]>::try_from(args) {
+        Ok([a, b]) => constructor(Box::new(a), Box::new(b)),
+        Err(_) => unknown(),
+    }
+}
+
+/// Splits function arguments on the commas that aren't inside nested calls
+fn split_args(args: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in args.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&args[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&args[start..]);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Like Basic Touch's description: 120% efficiency for carpenters from level 31
+    const EFFICIENCY: &str = "Efficiency: <If(Equal(PlayerParameter(68),8))><If(GreaterThanOrEqualTo(PlayerParameter(69),31))>120<Else/>100</If><Else/>100</If>%";
+
+    #[test]
+    fn picks_branches_by_job_and_level() {
+        let markup = Markup::parse(EFFICIENCY).unwrap();
+        let render = |job_id, level| markup.render(&Parameters::for_job(job_id, level));
+        assert_eq!(render(8, 30), "Efficiency: 100%");
+        assert_eq!(render(8, 31), "Efficiency: 120%");
+        assert_eq!(render(9, 31), "Efficiency: 100%");
+        assert_eq!(markup.to_string(), EFFICIENCY);
+    }
+
+    #[test]
+    fn evaluates_values() {
+        let markup = Markup::parse(
+            "Level <Value>PlayerParameter(69)</Value>, <If(LessThan(PlayerParameter(1),0))>x<Else/>y</If>",
+        )
+        .unwrap();
+        let parameters = Parameters::new().with(LEVEL_PARAMETER, 42);
+        assert_eq!(markup.render(&parameters), "Level 42, y");
+        assert_eq!(markup.render(&parameters.with(1, -1)), "Level 42, x");
+    }
+
+    #[test]
+    fn splits_text_by_level() {
+        let markup = Markup::parse(&format!(
+            "{EFFICIENCY}<If(GreaterThanOrEqualTo(PlayerParameter(69),1))>.<Else/>!</If>"
+        ))
+        .unwrap();
+        assert_eq!(markup.level_thresholds(), BTreeSet::from([1, 31]));
+        assert_eq!(
+            markup.by_level(8, Format::Plain),
+            [
+                (1, String::from("Efficiency: 100%.")),
+                (31, String::from("Efficiency: 120%.")),
+            ]
+        );
+        // the text doesn't change for other jobs
+        assert_eq!(
+            markup.by_level(9, Format::Plain),
+            [(1, String::from("Efficiency: 100%."))]
+        );
+    }
+
+    #[test]
+    fn finds_thresholds_for_every_comparison() {
+        let thresholds = |condition: &str| {
+            Markup::parse(&format!("<If({condition})>a<Else/>b</If>"))
+                .unwrap()
+                .level_thresholds()
+        };
+        let level = "PlayerParameter(69)";
+        assert_eq!(thresholds(&format!("GreaterThan({level},30)")), [31].into());
+        assert_eq!(thresholds(&format!("LessThan({level},30)")), [30].into());
+        assert_eq!(
+            thresholds(&format!("LessThanOrEqualTo({level},30)")),
+            [31].into()
+        );
+        assert_eq!(thresholds(&format!("Equal({level},30)")), [30, 31].into());
+        assert_eq!(
+            thresholds(&format!("NotEqual(30,{level})")),
+            [30, 31].into()
+        );
+        assert_eq!(thresholds(&format!("GreaterThan(30,{level})")), [30].into());
+        assert_eq!(
+            thresholds(&format!("GreaterThanOrEqualTo(30,{level})")),
+            [31].into()
+        );
+        // numbers that aren't levels, and other parameters, are ignored
+        assert_eq!(thresholds(&format!("LessThan({level},-5)")), [].into());
+        assert_eq!(thresholds("GreaterThan(PlayerParameter(68),30)"), [].into());
+
+        let markup =
+            Markup::parse(&format!("<If(LessThan({level},50))>low<Else/>high</If>")).unwrap();
+        assert_eq!(
+            markup.by_level(8, Format::Plain),
+            [(1, String::from("low")), (50, String::from("high"))]
+        );
+    }
+
+    #[test]
+    fn reports_error_positions() {
+        let error = |text| Markup::parse(text).unwrap_err();
+        assert_eq!(
+            error("ab<Else/>"),
+            ParseError {
+                position: 9,
+                message: String::from("`Else` or `/If` without `If`"),
+            }
+        );
+        assert_eq!(error("<If(1)>then").position, 11);
+        assert_eq!(
+            error("a<UIForeground>F201F8"),
+            ParseError {
+                position: 15,
+                message: String::from("missing `</UIForeground>`"),
+            }
+        );
+    }
+
+    #[test]
+    fn reads_labeled_numbers() {
+        let text = "Increases progress.\nEfficiency: 100% (200%)\n成功率：90%\nNote: none";
+        assert_eq!(
+            labeled_numbers(text),
+            BTreeMap::from([
+                (String::from("Efficiency"), 100),
+                (String::from("成功率"), 90),
+            ])
+        );
+    }
+
+    #[test]
+    fn keeps_unknown_expressions() {
+        let text = "<If(Equal(ObjectParameter(1),ObjectParameter(2)))>same<Else/>different</If>";
+        let markup = Markup::parse(text).unwrap();
+        assert_eq!(markup.to_string(), text);
+        // unknown expressions are 0, so they're equal
+        assert_eq!(markup.render(&Parameters::new()), "same");
+
+        let markup = Markup::parse("<Value>Equal(1)</Value>").unwrap();
+        assert_eq!(
+            markup.0,
+            [Node::Value(Expr::Unknown(String::from("Equal(1)")))]
+        );
+    }
+}
@@ -0,0 +1,183 @@
+use std::fmt::Write;
+
+use super::{color_index, Node};
+
+/// What game text is converted into
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// Text without formatting. Soft hyphens and non-breaking spaces are kept as U+00AD and
+    /// U+00A0, as translated item names always had them, so that long names still break where
+    /// the game breaks them. Strip them before comparing text, like the lookup files do.
+    #[default]
+    Plain,
+    /// Escaped HTML. Colors become `<span class="fg-{n}">` and `<span class="glow-{n}">`,
+    /// where `n` is the UIColor row.
+    Html,
+    /// Markdown with emphasis. Colors are dropped.
+    Markdown,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Span {
+    Foreground,
+    Glow,
+}
+
+impl Span {
+    fn class(self) -> &'static str {
+        match self {
+            Span::Foreground => "fg",
+            Span::Glow => "glow",
+        }
+    }
+}
+
+struct Writer {
+    format: Format,
+    output: String,
+    /// Open color spans, innermost last
+    spans: Vec<(Span, u32)>,
+}
+
+/// Writes evaluated nodes, i.e. without conditions or values
+pub(super) fn write(nodes: &[Node], format: Format) -> String {
+    let mut writer = Writer {
+        format,
+        output: String::new(),
+        spans: vec![],
+    };
+    for node in nodes {
+        writer.node(node);
+    }
+    writer.finish()
+}
+
+impl Writer {
+    fn node(&mut self, node: &Node) {
+        match (node, self.format) {
+            (Node::Text(text), _) => self.text(text),
+            (Node::Emphasis(true), Format::Html) => self.output.push_str("<em>"),
+            (Node::Emphasis(false), Format::Html) => self.output.push_str("</em>"),
+            (Node::Emphasis(_), Format::Markdown) => self.output.push('*'),
+            (Node::SoftHyphen, Format::Html) => self.output.push_str("&shy;"),
+            (Node::SoftHyphen, _) => self.output.push('\u{00AD}'),
+            (Node::NonBreakingSpace, Format::Html) => self.output.push_str("&nbsp;"),
+            (Node::NonBreakingSpace, _) => self.output.push('\u{00A0}'),
+            (Node::Indent, _) => self.output.push(' '),
+            (Node::Foreground(code), Format::Html) => self.color(Span::Foreground, code),
+            (Node::Glow(code), Format::Html) => self.color(Span::Glow, code),
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        for c in text.chars() {
+            match (c, self.format) {
+                // sheets use CRLF line endings, which are normalized to LF
+                ('\r', _) => {}
+                ('\n', Format::Html) => self.output.push_str("<br>"),
+                ('&', Format::Html) => self.output.push_str("&amp;"),
+                ('<', Format::Html) => self.output.push_str("&lt;"),
+                ('>', Format::Html) => self.output.push_str("&gt;"),
+                ('"', Format::Html) => self.output.push_str("&quot;"),
+                ('\n', Format::Markdown) => self.output.push_str("  \n"),
+                ('\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>' | '#', Format::Markdown) => {
+                    self.output.push('\\');
+                    self.output.push(c);
+                }
+                _ => self.output.push(c),
+            }
+        }
+    }
+
+    fn color(&mut self, span: Span, code: &str) {
+        match color_index(code) {
+            Some(index) => {
+                write!(self.output, r#"<span class="{}-{index}">"#, span.class()).unwrap();
+                self.spans.push((span, index));
+            }
+            None => {
+                // a reset closes the innermost span of its kind, so any spans opened inside it
+                // are closed and reopened to keep the HTML nested
+                let Some(position) = self.spans.iter().rposition(|(s, _)| *s == span) else {
+                    return;
+                };
+                let reopened = self.spans.split_off(position + 1);
+                self.spans.pop();
+                for _ in 0..=reopened.len() {
+                    self.output.push_str("</span>");
+                }
+                for (span, index) in reopened {
+                    write!(self.output, r#"<span class="{}-{index}">"#, span.class()).unwrap();
+                    self.spans.push((span, index));
+                }
+            }
+        }
+    }
+
+    fn finish(mut self) -> String {
+        for _ in self.spans.drain(..) {
+            self.output.push_str("</span>");
+        }
+        self.output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Format;
+    use crate::markup::{Markup, Parameters};
+
+    fn format(text: &str, format: Format) -> String {
+        Markup::parse(text)
+            .unwrap()
+            .format(&Parameters::new(), format)
+    }
+
+    /// A label in one color with a glow, like in action descriptions, whose color is reset
+    /// before its glow
+    const LABEL: &str = "<UIForeground>F201F8</UIForeground><UIGlow>F201F9</UIGlow>Efficiency:<UIForeground>01</UIForeground> base<UIGlow>01</UIGlow> 100%";
+
+    const SPECIAL: &str = "<Emphasis>Hi-Cordial</Emphasis> [a*b_c] & \"d\" > #1\r\nLong<SoftHyphen/>name<NonBreakingSpace/>x<Indent/>y<Unknown/>";
+
+    #[test]
+    fn plain() {
+        assert_eq!(format(LABEL, Format::Plain), "Efficiency: base 100%");
+        assert_eq!(
+            format(SPECIAL, Format::Plain),
+            "Hi-Cordial [a*b_c] & \"d\" > #1\nLong\u{00AD}name\u{00A0}x y"
+        );
+    }
+
+    #[test]
+    fn html() {
+        assert_eq!(
+            format(LABEL, Format::Html),
+            concat!(
+                r#"<span class="fg-504"><span class="glow-505">Efficiency:</span></span>"#,
+                r#"<span class="glow-505"> base</span> 100%"#,
+            )
+        );
+        assert_eq!(
+            format(SPECIAL, Format::Html),
+            "<em>Hi-Cordial</em> [a*b_c] &amp; &quot;d&quot; &gt; #1<br>Long&shy;name&nbsp;x y"
+        );
+        // spans left open are closed at the end, and resets without a span are ignored
+        assert_eq!(
+            format(
+                "<UIGlow>01</UIGlow>a<UIForeground>03</UIForeground>b < c",
+                Format::Html
+            ),
+            r#"a<span class="fg-2">b &lt; c</span>"#
+        );
+    }
+
+    #[test]
+    fn markdown() {
+        assert_eq!(format(LABEL, Format::Markdown), "Efficiency: base 100%");
+        assert_eq!(
+            format(SPECIAL, Format::Markdown),
+            "*Hi-Cordial* \\[a\\*b\\_c\\] & \"d\" \\> \\#1  \nLong\u{00AD}name\u{00A0}x y"
+        );
+    }
+}
@@ -1,13 +1,23 @@
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
+use std::collections::HashMap;
 
-use crate::utils::bool_string;
+use crate::markup::Markup;
+
+/// A record type that's read from a game sheet, i.e. `{NAME}.csv` in the data directory
+pub trait Sheet: DeserializeOwned {
+    const NAME: &'static str;
+
+    /// The row's key, i.e. its `#` column
+    fn id(&self) -> u32;
+}
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct ActionRecord {
     #[serde(rename = "#")]
     pub id: u32,
 
-    #[serde(rename = "Name")]
+    #[serde(rename = "Name", deserialize_with = "crate::markup::deserialize_plain")]
     pub name: String,
 
     #[serde(rename = "Icon")]
@@ -19,24 +29,75 @@ pub struct ActionRecord {
     #[serde(rename = "ClassJob")]
     pub class_job: i32,
 
+    #[serde(rename = "ClassJobLevel")]
+    pub level: u32,
+
+    /// CP for crafting actions
+    #[serde(rename = "PrimaryCostValue")]
+    pub cost: u32,
+
     #[serde(rename = "IsPlayerAction")]
-    #[serde(deserialize_with = "bool_string")]
     pub is_player_action: bool,
 }
 
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClassJobRecord {
+    #[serde(rename = "#")]
+    pub id: u32,
+
+    #[serde(rename = "Name", deserialize_with = "crate::markup::deserialize_plain")]
+    pub name: String,
+
+    #[serde(
+        rename = "Abbreviation",
+        deserialize_with = "crate::markup::deserialize_plain"
+    )]
+    pub abbreviation: String,
+
+    #[serde(rename = "ClassJobCategory")]
+    pub category: u32,
+
+    /// The job's position among crafters or gatherers, which recipes refer to it by
+    #[serde(rename = "DohDolJobIndex")]
+    pub doh_dol_index: i32,
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct CraftActionRecord {
     #[serde(rename = "#")]
     pub id: u32,
 
-    #[serde(rename = "Name")]
+    #[serde(rename = "Name", deserialize_with = "crate::markup::deserialize_plain")]
     pub name: String,
 
+    #[serde(
+        rename = "Description",
+        deserialize_with = "crate::markup::deserialize_markup"
+    )]
+    pub description: Markup,
+
     #[serde(rename = "Icon")]
     pub icon: u32,
 
     #[serde(rename = "ClassJob")]
     pub class_job: i32,
+
+    #[serde(rename = "ClassJobLevel")]
+    pub level: u32,
+
+    #[serde(rename = "QuestRequirement")]
+    pub quest_requirement: u32,
+
+    #[serde(rename = "Specialist")]
+    pub specialist: bool,
+
+    #[serde(rename = "Cost")]
+    pub cost: u32,
+
+    /// Every column by name, which includes each crafting job's id for the action in the
+    /// column named after the job's abbreviation
+    #[serde(rename = "*")]
+    pub columns: HashMap<String, String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -51,67 +112,35 @@ pub struct ItemActionRecord {
     pub data_1: u32,
 }
 
-#[derive(Debug, Clone, Copy, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct ItemFoodRecord {
     #[serde(rename = "#")]
     pub id: u32,
 
-    #[serde(rename = "BaseParam[0]")]
-    pub param_0: u32,
-
-    #[serde(rename = "IsRelative[0]")]
-    #[serde(deserialize_with = "bool_string")]
-    pub param_0_relative: bool,
-
-    #[serde(rename = "Value[0]")]
-    pub param_0_value: i32,
-
-    #[serde(rename = "Max[0]")]
-    pub param_0_max: u32,
-
-    #[serde(rename = "Value{HQ}[0]")]
-    pub param_0_hq_value: i32,
-
-    #[serde(rename = "Max{HQ}[0]")]
-    pub param_0_hq_max: u32,
-
-    #[serde(rename = "BaseParam[1]")]
-    pub param_1: u32,
-
-    #[serde(rename = "IsRelative[1]")]
-    #[serde(deserialize_with = "bool_string")]
-    pub param_1_relative: bool,
-
-    #[serde(rename = "Value[1]")]
-    pub param_1_value: i32,
-
-    #[serde(rename = "Max[1]")]
-    pub param_1_max: u32,
-
-    #[serde(rename = "Value{HQ}[1]")]
-    pub param_1_hq_value: i32,
-
-    #[serde(rename = "Max{HQ}[1]")]
-    pub param_1_hq_max: u32,
+    #[serde(rename = "BaseParam[]")]
+    pub params: Vec<ItemFoodParam>,
+}
 
-    #[serde(rename = "BaseParam[2]")]
-    pub param_2: u32,
+/// One `BaseParam[n]` slot of an `ItemFood` row, along with its other `[n]` columns
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct ItemFoodParam {
+    #[serde(rename = "BaseParam")]
+    pub param: u32,
 
-    #[serde(rename = "IsRelative[2]")]
-    #[serde(deserialize_with = "bool_string")]
-    pub param_2_relative: bool,
+    #[serde(rename = "IsRelative")]
+    pub relative: bool,
 
-    #[serde(rename = "Value[2]")]
-    pub param_2_value: i32,
+    #[serde(rename = "Value")]
+    pub value: i32,
 
-    #[serde(rename = "Max[2]")]
-    pub param_2_max: u32,
+    #[serde(rename = "Max")]
+    pub max: u32,
 
-    #[serde(rename = "Value{HQ}[2]")]
-    pub param_2_hq_value: i32,
+    #[serde(rename = "Value{HQ}")]
+    pub hq_value: i32,
 
-    #[serde(rename = "Max{HQ}[2]")]
-    pub param_2_hq_max: u32,
+    #[serde(rename = "Max{HQ}")]
+    pub hq_max: u32,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -119,7 +148,7 @@ pub struct ItemRecord {
     #[serde(rename = "#")]
     pub id: u32,
 
-    #[serde(rename = "Name")]
+    #[serde(rename = "Name", deserialize_with = "crate::markup::deserialize_plain")]
     pub name: String,
 
     #[serde(rename = "Level{Item}")]
@@ -132,25 +161,30 @@ pub struct ItemRecord {
     pub item_action: u32,
 
     #[serde(rename = "CanBeHq")]
-    #[serde(deserialize_with = "bool_string")]
     pub can_hq: bool,
 
     #[serde(rename = "EquipSlotCategory")]
     pub equip_slot_category: u32,
 }
 
-#[allow(dead_code)]
 #[derive(Debug, Deserialize)]
 pub struct RecipeRecord {
     #[serde(rename = "#")]
     pub id: u32,
 
+    /// The crafting job, as its `DohDolJobIndex` in ClassJob.csv
+    #[serde(rename = "CraftType")]
+    pub craft_type: u32,
+
     #[serde(rename = "RecipeLevelTable")]
     pub recipe_level: u32,
 
     #[serde(rename = "Item{Result}")]
     pub result_item_id: u32,
 
+    #[serde(rename = "Amount{Result}")]
+    pub result_amount: u32,
+
     #[serde(rename = "DifficultyFactor")]
     pub progress_factor: u32,
 
@@ -160,66 +194,66 @@ pub struct RecipeRecord {
     #[serde(rename = "DurabilityFactor")]
     pub durability_factor: u32,
 
+    #[serde(rename = "RequiredQuality")]
+    pub required_quality: u32,
+
     #[serde(rename = "RequiredCraftsmanship")]
     pub required_craftsmanship: u32,
 
     #[serde(rename = "RequiredControl")]
     pub required_control: u32,
 
+    #[serde(rename = "QuickSynthCraftsmanship")]
+    pub quick_synth_craftsmanship: u32,
+
+    #[serde(rename = "QuickSynthControl")]
+    pub quick_synth_control: u32,
+
+    #[serde(rename = "SecretRecipeBook")]
+    pub secret_recipe_book: u32,
+
+    #[serde(rename = "Quest")]
+    pub quest: u32,
+
+    #[serde(rename = "CanQuickSynth")]
+    pub can_quick_synth: bool,
+
     #[serde(rename = "CanHq")]
-    #[serde(deserialize_with = "bool_string")]
     pub can_hq: bool,
 
+    #[serde(rename = "ExpRewarded")]
+    pub exp_rewarded: bool,
+
+    #[serde(rename = "Status{Required}")]
+    pub required_status: u32,
+
+    #[serde(rename = "Item{Required}")]
+    pub required_item: u32,
+
     #[serde(rename = "IsSpecializationRequired")]
-    #[serde(deserialize_with = "bool_string")]
     pub is_spec: bool,
 
     #[serde(rename = "IsExpert")]
-    #[serde(deserialize_with = "bool_string")]
     pub is_expert: bool,
 
     #[serde(rename = "MaterialQualityFactor")]
     pub material_quality_factor: u32,
 
-    #[serde(rename = "Item{Ingredient}[0]")]
-    pub item_0: i32,
-    #[serde(rename = "Amount{Ingredient}[0]")]
-    pub amount_0: u32,
-
-    #[serde(rename = "Item{Ingredient}[1]")]
-    pub item_1: i32,
-    #[serde(rename = "Amount{Ingredient}[1]")]
-    pub amount_1: u32,
-
-    #[serde(rename = "Item{Ingredient}[2]")]
-    pub item_2: i32,
-    #[serde(rename = "Amount{Ingredient}[2]")]
-    pub amount_2: u32,
-
-    #[serde(rename = "Item{Ingredient}[3]")]
-    pub item_3: i32,
-    #[serde(rename = "Amount{Ingredient}[3]")]
-    pub amount_3: u32,
-
-    #[serde(rename = "Item{Ingredient}[4]")]
-    pub item_4: i32,
-    #[serde(rename = "Amount{Ingredient}[4]")]
-    pub amount_4: u32,
-
-    #[serde(rename = "Item{Ingredient}[5]")]
-    pub item_5: i32,
-    #[serde(rename = "Amount{Ingredient}[5]")]
-    pub amount_5: u32,
-
-    #[serde(rename = "Item{Ingredient}[6]")]
-    pub item_6: i32,
-    #[serde(rename = "Amount{Ingredient}[6]")]
-    pub amount_6: u32,
-
-    #[serde(rename = "Item{Ingredient}[7]")]
-    pub item_7: i32,
-    #[serde(rename = "Amount{Ingredient}[7]")]
-    pub amount_7: u32,
+    #[serde(rename = "PatchNumber")]
+    pub patch_number: u32,
+
+    #[serde(rename = "Item{Ingredient}[]")]
+    pub ingredients: Vec<RecipeIngredient>,
+}
+
+/// One `Item{Ingredient}[n]` slot of a `Recipe` row
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct RecipeIngredient {
+    #[serde(rename = "Item{Ingredient}")]
+    pub item: i32,
+
+    #[serde(rename = "Amount{Ingredient}")]
+    pub amount: u32,
 }
 
 #[derive(Debug, Deserialize, PartialEq, Eq, Hash)]
@@ -260,36 +294,30 @@ pub struct RecipeLevelRecord {
 
 #[derive(Debug, Deserialize)]
 pub struct RecipeLookupRecord {
-    #[serde(rename = "CRP")]
-    pub crp: u32,
-
-    #[serde(rename = "BSM")]
-    pub bsm: u32,
-
-    #[serde(rename = "ARM")]
-    pub arm: u32,
-
-    #[serde(rename = "GSM")]
-    pub gsm: u32,
-
-    #[serde(rename = "LTW")]
-    pub ltw: u32,
-
-    #[serde(rename = "WVR")]
-    pub wvr: u32,
-
-    #[serde(rename = "ALC")]
-    pub alc: u32,
+    /// The id of the item that the recipes craft
+    #[serde(rename = "#")]
+    pub id: u32,
 
-    #[serde(rename = "CUL")]
-    pub cul: u32,
+    /// Every column by name, which includes each crafting job's recipe in the column named
+    /// after the job's abbreviation
+    #[serde(rename = "*")]
+    pub columns: HashMap<String, String>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct StatusRecord {
-    #[serde(rename = "Name")]
+    #[serde(rename = "#")]
+    pub id: u32,
+
+    #[serde(rename = "Name", deserialize_with = "crate::markup::deserialize_plain")]
     pub name: String,
 
+    #[serde(
+        rename = "Description",
+        deserialize_with = "crate::markup::deserialize_markup"
+    )]
+    pub description: Markup,
+
     #[serde(rename = "Icon")]
     pub icon: u32,
 
@@ -299,3 +327,83 @@ pub struct StatusRecord {
     #[serde(rename = "ClassJobCategory")]
     pub category: u32,
 }
+
+impl Sheet for ActionRecord {
+    const NAME: &'static str = "Action";
+
+    fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+impl Sheet for ClassJobRecord {
+    const NAME: &'static str = "ClassJob";
+
+    fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+impl Sheet for CraftActionRecord {
+    const NAME: &'static str = "CraftAction";
+
+    fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+impl Sheet for ItemActionRecord {
+    const NAME: &'static str = "ItemAction";
+
+    fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+impl Sheet for ItemFoodRecord {
+    const NAME: &'static str = "ItemFood";
+
+    fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+impl Sheet for ItemRecord {
+    const NAME: &'static str = "Item";
+
+    fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+impl Sheet for RecipeRecord {
+    const NAME: &'static str = "Recipe";
+
+    fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+impl Sheet for RecipeLevelRecord {
+    const NAME: &'static str = "RecipeLevelTable";
+
+    fn id(&self) -> u32 {
+        self.recipe_level
+    }
+}
+
+impl Sheet for RecipeLookupRecord {
+    const NAME: &'static str = "RecipeLookup";
+
+    fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+impl Sheet for StatusRecord {
+    const NAME: &'static str = "Status";
+
+    fn id(&self) -> u32 {
+        self.id
+    }
+}
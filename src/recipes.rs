@@ -1,67 +1,61 @@
-use serde::Serialize;
-use std::collections::HashMap;
-use std::hash::{Hash, Hasher};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
 
-use crate::models::{ItemRecord, RecipeLevelRecord, RecipeLookupRecord, RecipeRecord};
-use crate::utils::{calculate_hash, read_csv_data, write_json_file};
+use crate::conditions::Conditions;
+use crate::error::Result;
+use crate::game_data::GameData;
+use crate::models::{
+    ClassJobRecord, ItemRecord, RecipeLevelRecord, RecipeLookupRecord, RecipeRecord,
+};
+use crate::translated_items::{language_tag, ItemTranslations};
+use crate::utils::write_json_file;
+use crate::validate::SheetSchema;
 
-pub fn build_recipes() -> HashMap<u32, String> {
-    let mut relevant_items = HashMap::new();
+pub fn sheets() -> Vec<SheetSchema> {
+    vec![
+        SheetSchema::of::<ClassJobRecord>(),
+        SheetSchema::of::<ItemRecord>(),
+        SheetSchema::of::<RecipeLookupRecord>(),
+        SheetSchema::of::<RecipeLevelRecord>(),
+        SheetSchema::of::<RecipeRecord>(),
+    ]
+}
 
-    let mut items = HashMap::new();
-    for item in read_csv_data::<ItemRecord>("data/Item.csv") {
-        if item.name.trim().is_empty() {
-            continue;
-        }
-        items.insert(item.id, item);
-    }
+pub struct Recipes {
+    pub recipes: Vec<RecipeOutput>,
+    /// English names of every result and ingredient item, keyed by item id
+    pub item_names: HashMap<u32, String>,
+    pub report: MergeReport,
+}
 
-    let mut recipe_jobs: HashMap<u32, Vec<&str>> = HashMap::new();
-    for recipe_lookup in read_csv_data::<RecipeLookupRecord>("data/RecipeLookup.csv") {
-        for (recipe_id, job) in &vec![
-            (recipe_lookup.crp, "CRP"),
-            (recipe_lookup.bsm, "BSM"),
-            (recipe_lookup.arm, "ARM"),
-            (recipe_lookup.gsm, "GSM"),
-            (recipe_lookup.ltw, "LTW"),
-            (recipe_lookup.wvr, "WVR"),
-            (recipe_lookup.alc, "ALC"),
-            (recipe_lookup.cul, "CUL"),
-        ] {
-            if *recipe_id > 0 {
-                recipe_jobs
-                    .entry(*recipe_id)
-                    .and_modify(|e| e.push(job))
-                    .or_insert_with(|| vec![job]);
-            }
-        }
-    }
+pub fn build_recipes(data: &GameData) -> Result<Recipes> {
+    let mut relevant_items = HashMap::new();
 
-    let mut recipe_levels = HashMap::new();
-    for recipe_level_record in read_csv_data::<RecipeLevelRecord>("data/RecipeLevelTable.csv") {
-        recipe_levels.insert(recipe_level_record.recipe_level, recipe_level_record);
+    // only recipes that can be looked up in game are relevant
+    let mut looked_up_recipes = HashSet::new();
+    for recipe_lookup in data.recipe_lookups()? {
+        for recipe in recipe_lookup.recipes(data)? {
+            looked_up_recipes.insert(recipe.id);
+        }
     }
 
-    let mut unique_recipes: HashMap<u64, RecipeOutput> = HashMap::new();
-    for recipe in read_csv_data::<RecipeRecord>("data/Recipe.csv") {
-        if recipe.result_item_id == 0 {
+    let mut recipes_by_item: HashMap<u32, Vec<RecipeOutput>> = HashMap::new();
+    for recipe in data.recipes()? {
+        let Some(item) = recipe.result_item(data)? else {
             continue;
-        }
-
-        let item = items
-            .get(&recipe.result_item_id)
-            .unwrap_or_else(|| panic!("no item value for item id {:?}", &recipe.result_item_id));
+        };
 
         relevant_items.insert(item.id, item.name.clone());
 
-        let Some(jobs) = recipe_jobs.get(&recipe.id) else {
-            println!("no job value for recipe id {:?}", &recipe.id);
+        if !looked_up_recipes.contains(&recipe.id) {
+            println!("no lookup for recipe id {:?}", &recipe.id);
             continue;
-        };
+        }
+        let job = recipe.job(data)?;
 
-        let recipe_level = recipe_levels
-            .get(&recipe.recipe_level)
-            .unwrap_or_else(|| panic!("no entry for recipe level {:?}", &recipe.recipe_level));
+        let recipe_level = recipe.level(data)?;
 
         fn apply_factor(base_value: u32, factor: u32) -> u32 {
             (f64::from(base_value * factor) / 100.0).floor() as u32
@@ -73,41 +67,27 @@ pub fn build_recipes() -> HashMap<u32, String> {
         let quality = apply_factor(recipe_level.quality, recipe.quality_factor);
         let durability = apply_factor(recipe_level.durability, recipe.durability_factor);
 
-        let mut ingredients: Vec<Ingredient> = [
-            (recipe.item_0, recipe.amount_0),
-            (recipe.item_1, recipe.amount_1),
-            (recipe.item_2, recipe.amount_2),
-            (recipe.item_3, recipe.amount_3),
-            (recipe.item_4, recipe.amount_4),
-            (recipe.item_5, recipe.amount_5),
-            (recipe.item_6, recipe.amount_6),
-            (recipe.item_7, recipe.amount_7),
-        ]
-        .iter()
-        .filter_map(|(item_id, amount)| {
-            if *item_id <= 0 || *amount == 0 {
-                return None;
-            };
-
-            let item_id = *item_id as u32;
-            let item = items.get(&item_id).unwrap();
-
+        let mut ingredients: Vec<Ingredient> = vec![];
+        for (item, amount) in recipe.ingredient_items(data)? {
             relevant_items.insert(item.id, item.name.clone());
 
-            Some(Ingredient {
+            ingredients.push(Ingredient {
+                item_id: item.id,
                 name: item.name.clone(),
-                amount: *amount,
+                amount,
                 item_level: item.item_level,
                 can_hq: item.can_hq,
-            })
-        })
-        .collect();
+            });
+        }
 
-        ingredients.sort_by_key(|item| item.name.clone());
+        ingredients.sort_by(|a, b| (&a.name, a.item_id).cmp(&(&b.name, b.item_id)));
 
         let mut recipe_output = RecipeOutput {
+            recipe_ids: vec![recipe.id],
+            item_id: item.id,
             name: item.name.clone(),
-            jobs: jobs.iter().map(|&job| String::from(job)).collect(),
+            jobs: vec![job.id],
+            job_names: vec![],
             job_level: recipe_level.job_level,
             recipe_level: recipe.recipe_level,
             item_level: if item.equip_slot_category > 0 {
@@ -131,93 +111,299 @@ pub fn build_recipes() -> HashMap<u32, String> {
             is_specialist: recipe.is_spec,
             is_expert: recipe.is_expert,
             conditions_flag: recipe_level.conditions_flag,
+            conditions: Conditions::from_flag(recipe_level.conditions_flag),
             can_hq: recipe.can_hq,
             material_quality: recipe.material_quality_factor,
+            required_craftsmanship: recipe.required_craftsmanship,
+            required_control: recipe.required_control,
+            required_quality: recipe.required_quality,
+            can_quick_synth: recipe.can_quick_synth,
+            quick_synth_craftsmanship: recipe.quick_synth_craftsmanship,
+            quick_synth_control: recipe.quick_synth_control,
+            exp_rewarded: recipe.exp_rewarded,
+            result_amount: recipe.result_amount,
+            required_status: recipe.required_status,
+            required_item: recipe.required_item,
+            secret_recipe_book: recipe.secret_recipe_book,
+            quest: recipe.quest,
+            patch_number: recipe.patch_number,
             ingredients,
         };
 
-        let key = calculate_hash(&recipe_output);
-
-        unique_recipes
-            .entry(key)
-            .and_modify(|existing_recipe| {
+        // recipes for the same item are merged if every crafting field matches, and recipes
+        // are read in id order, so the existing recipe has the lowest id
+        let variants = recipes_by_item.entry(item.id).or_default();
+        match variants
+            .iter_mut()
+            .find(|existing| differing_fields(existing, &recipe_output).is_empty())
+        {
+            Some(existing_recipe) => {
                 existing_recipe.jobs.append(&mut recipe_output.jobs);
-            })
-            .or_insert(recipe_output);
+                existing_recipe
+                    .recipe_ids
+                    .append(&mut recipe_output.recipe_ids);
+            }
+            None => variants.push(recipe_output),
+        }
+    }
+
+    let crafting_jobs = data.crafting_jobs()?;
+    let job_position = |job_id: &u32| crafting_jobs.position(*job_id);
+
+    let mut report = MergeReport::default();
+    let mut recipe_output = vec![];
+    for variants in recipes_by_item.into_values() {
+        if variants.len() > 1 {
+            let fields = variants
+                .iter()
+                .flat_map(|a| variants.iter().flat_map(|b| differing_fields(a, b)))
+                .collect::<BTreeSet<_>>();
+            report.near_collisions.push(NearCollision {
+                item_id: variants[0].item_id,
+                name: variants[0].name.clone(),
+                recipe_ids: variants.iter().map(|v| v.recipe_ids.clone()).collect(),
+                fields: fields.into_iter().collect(),
+            });
+        }
+
+        for mut recipe in variants {
+            recipe.jobs.sort_by_key(job_position);
+            recipe.jobs.dedup();
+            recipe.recipe_ids.sort_unstable();
+            recipe.recipe_ids.dedup();
+            let jobs: Vec<_> = crafting_jobs
+                .iter()
+                .filter(|job| recipe.jobs.contains(&job.id))
+                .collect();
+            recipe.job_names = jobs.iter().map(|job| job.name.clone()).collect();
+
+            if recipe.recipe_ids.len() > 1 {
+                report.merges.push(Merge {
+                    item_id: recipe.item_id,
+                    name: recipe.name.clone(),
+                    recipe_ids: recipe.recipe_ids.clone(),
+                    jobs: jobs.iter().map(|job| job.abbreviation.clone()).collect(),
+                });
+            }
+            recipe_output.push(recipe);
+        }
     }
 
-    let mut recipe_output = unique_recipes.into_values().collect::<Vec<RecipeOutput>>();
-    recipe_output.sort_by(|a, b| a.name.cmp(&b.name));
-    write_json_file(&recipe_output, "output/recipes.json");
+    recipe_output.sort_by(|a, b| (&a.name, &a.recipe_ids).cmp(&(&b.name, &b.recipe_ids)));
+    report.merges.sort_by_key(|merge| merge.recipe_ids.clone());
+    report
+        .near_collisions
+        .sort_by_key(|collision| collision.item_id);
 
-    relevant_items
+    Ok(Recipes {
+        recipes: recipe_output,
+        item_names: relevant_items,
+        report,
+    })
 }
 
-#[derive(Debug, Serialize)]
-struct RecipeOutput {
-    name: String,
-    jobs: Vec<String>,
-    job_level: u32,
-    recipe_level: u32,
-    item_level: u32,
-    equip_level: u32,
-    stars: u32,
-    progress: u32,
-    quality: u32,
-    durability: u32,
-    progress_div: u32,
-    progress_mod: u32,
-    quality_div: u32,
-    quality_mod: u32,
-    is_specialist: bool,
-    is_expert: bool,
-    conditions_flag: u32,
-    can_hq: bool,
-    material_quality: u32,
-    ingredients: Vec<Ingredient>,
+pub fn write_recipes(recipes: &[RecipeOutput], output_dir: &Path) -> Result<()> {
+    write_json_file(&recipes, output_dir.join("recipes.json"))
 }
 
-#[derive(Debug, Serialize)]
-struct Ingredient {
-    name: String,
-    amount: u32,
-    item_level: u32,
-    can_hq: bool,
+/// Copies of the recipes with item, ingredient and job names translated, keyed by language
+/// tag. They keep the order of recipes.json, and names without a translation stay in English.
+pub fn build_localized_recipes(
+    data: &GameData,
+    recipes: &[RecipeOutput],
+    items: &ItemTranslations,
+) -> Result<BTreeMap<String, Vec<RecipeOutput>>> {
+    let mut recipes_by_language = BTreeMap::new();
+
+    for (language, localized) in data.languages() {
+        let translate = |item_id: u32, name: &mut String| {
+            let translation = items
+                .get(&item_id)
+                .and_then(|names| names.translations.get(language));
+            if let Some(translation) = translation {
+                name.clone_from(translation);
+            }
+        };
+        let class_jobs = localized.optional_class_jobs()?;
+
+        let mut localized_recipes = recipes.to_vec();
+        for recipe in &mut localized_recipes {
+            translate(recipe.item_id, &mut recipe.name);
+            for ingredient in &mut recipe.ingredients {
+                translate(ingredient.item_id, &mut ingredient.name);
+            }
+            for (job_id, name) in recipe.jobs.iter().zip(&mut recipe.job_names) {
+                let job = class_jobs
+                    .and_then(|class_jobs| class_jobs.get(*job_id))
+                    .filter(|job| !job.name.is_empty());
+                if let Some(job) = job {
+                    name.clone_from(&job.name);
+                }
+            }
+        }
+
+        recipes_by_language.insert(String::from(language_tag(language)), localized_recipes);
+    }
+
+    Ok(recipes_by_language)
 }
 
-// traits used to dedupe recipes across multiple jobs.
-// this is probably a bit overkill, though
-
-impl PartialEq for RecipeOutput {
-    fn eq(&self, other: &Self) -> bool {
-        self.name == other.name
-            && self.job_level == other.job_level
-            && self.recipe_level == other.recipe_level
-            && self.item_level == other.item_level
-            && self.equip_level == other.equip_level
-            && self.stars == other.stars
-            && self.progress == other.progress
-            && self.quality == other.quality
-            && self.durability == other.durability
-            && self.is_specialist == other.is_specialist
-            && self.is_expert == other.is_expert
+/// Writes one `recipes_{language tag}.json` file per language
+pub fn write_localized_recipes(
+    recipes_by_language: &BTreeMap<String, Vec<RecipeOutput>>,
+    output_dir: &Path,
+) -> Result<()> {
+    for (tag, recipes) in recipes_by_language {
+        write_json_file(recipes, output_dir.join(format!("recipes_{tag}.json")))?;
     }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecipeOutput {
+    /// Ids of every recipe that was merged into this one, one per job
+    pub recipe_ids: Vec<u32>,
+    pub item_id: u32,
+    pub name: String,
+    /// ClassJob ids of the jobs that can craft the recipe, in game order
+    pub jobs: Vec<u32>,
+    /// Names of `jobs`, in the file's language
+    pub job_names: Vec<String>,
+    pub job_level: u32,
+    pub recipe_level: u32,
+    pub item_level: u32,
+    pub equip_level: u32,
+    pub stars: u32,
+    pub progress: u32,
+    pub quality: u32,
+    pub durability: u32,
+    pub progress_div: u32,
+    pub progress_mod: u32,
+    pub quality_div: u32,
+    pub quality_mod: u32,
+    pub is_specialist: bool,
+    pub is_expert: bool,
+    /// Raw `ConditionsFlag`, which may include bits for unknown conditions
+    pub conditions_flag: u32,
+    /// Conditions that can occur during the craft, decoded from `conditions_flag`
+    pub conditions: Conditions,
+    pub can_hq: bool,
+    pub material_quality: u32,
+    /// Minimum craftsmanship needed to start the craft, or 0 if there's no minimum
+    pub required_craftsmanship: u32,
+    /// Minimum control needed to start the craft, or 0 if there's no minimum
+    pub required_control: u32,
+    /// Quality the craft must reach to succeed, or 0 if there's no requirement
+    pub required_quality: u32,
+    pub can_quick_synth: bool,
+    pub quick_synth_craftsmanship: u32,
+    pub quick_synth_control: u32,
+    pub exp_rewarded: bool,
+    /// Number of items crafted at once
+    pub result_amount: u32,
+    /// Status id that must be active to start the craft, or 0
+    pub required_status: u32,
+    /// Item id needed to start the craft, or 0
+    pub required_item: u32,
+    /// Master recipe book that unlocks the recipe, or 0 if it's always known
+    pub secret_recipe_book: u32,
+    /// Quest that unlocks the recipe, or 0
+    pub quest: u32,
+    /// Patch the recipe was added in, or 65535 if unknown
+    pub patch_number: u32,
+    pub ingredients: Vec<Ingredient>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Ingredient {
+    pub item_id: u32,
+    pub name: String,
+    pub amount: u32,
+    pub item_level: u32,
+    pub can_hq: bool,
 }
 
-impl Eq for RecipeOutput {}
-
-impl Hash for RecipeOutput {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.name.hash(state);
-        self.job_level.hash(state);
-        self.recipe_level.hash(state);
-        self.item_level.hash(state);
-        self.equip_level.hash(state);
-        self.stars.hash(state);
-        self.progress.hash(state);
-        self.quality.hash(state);
-        self.durability.hash(state);
-        self.is_specialist.hash(state);
-        self.is_expert.hash(state);
+/// Crafting fields that differ between two recipes. Recipes with no differing fields are the
+/// same recipe, shared by several jobs. Bookkeeping fields like the patch, unlocks and quick
+/// synthesis aren't compared, and a merged recipe keeps those of its lowest recipe id.
+fn differing_fields(a: &RecipeOutput, b: &RecipeOutput) -> Vec<&'static str> {
+    let mut fields = vec![];
+    macro_rules! compare {
+        ($($field:ident)*) => {$(
+            if a.$field != b.$field {
+                fields.push(stringify!($field));
+            }
+        )*};
+    }
+    compare! {
+        item_id name job_level recipe_level item_level equip_level stars progress quality
+        durability progress_div progress_mod quality_div quality_mod is_specialist is_expert
+        conditions_flag can_hq material_quality required_craftsmanship required_control
+        required_quality result_amount ingredients
+    }
+    fields
+}
+
+/// How recipes were merged across jobs
+#[derive(Debug, Default)]
+pub struct MergeReport {
+    /// Recipes that were identical across several jobs, and were merged
+    pub merges: Vec<Merge>,
+    /// Items with several recipes that weren't merged, because their fields differ
+    pub near_collisions: Vec<NearCollision>,
+}
+
+#[derive(Debug)]
+pub struct Merge {
+    pub item_id: u32,
+    pub name: String,
+    pub recipe_ids: Vec<u32>,
+    /// Job abbreviations
+    pub jobs: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct NearCollision {
+    pub item_id: u32,
+    pub name: String,
+    /// The merged recipe ids of each distinct recipe
+    pub recipe_ids: Vec<Vec<u32>>,
+    /// Fields that differ between any of the recipes
+    pub fields: Vec<&'static str>,
+}
+
+impl fmt::Display for MergeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} merged recipe(s), {} item(s) with differing recipes",
+            self.merges.len(),
+            self.near_collisions.len()
+        )?;
+        // the alternate format also lists every merge
+        if f.alternate() {
+            for merge in &self.merges {
+                write!(
+                    f,
+                    "\n  merged {:?} (item {}): recipes {:?} for {}",
+                    merge.name,
+                    merge.item_id,
+                    merge.recipe_ids,
+                    merge.jobs.join(", ")
+                )?;
+            }
+        }
+        for collision in &self.near_collisions {
+            write!(
+                f,
+                "\n  not merged {:?} (item {}): recipes {:?} differ in {}",
+                collision.name,
+                collision.item_id,
+                collision.recipe_ids,
+                collision.fields.join(", ")
+            )?;
+        }
+        Ok(())
     }
 }
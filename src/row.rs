@@ -0,0 +1,589 @@
+use serde::de::{
+    self,
+    value::{Error, MapDeserializer},
+    DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor,
+};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// Column positions in a sheet, by name
+pub struct Columns {
+    positions: HashMap<String, usize>,
+}
+
+impl Columns {
+    pub fn new<'h>(headers: impl IntoIterator<Item = &'h str>) -> Self {
+        let mut positions = HashMap::new();
+        for (position, header) in headers.into_iter().enumerate() {
+            // unnamed columns can't be referenced, and the first column with a name wins
+            if !header.is_empty() {
+                positions.entry(String::from(header)).or_insert(position);
+            }
+        }
+        Columns { positions }
+    }
+
+    /// Counts the consecutive `{base}[0]`, `{base}[1]`, ... columns
+    fn indexed_len(&self, base: &str) -> usize {
+        (0..)
+            .take_while(|i| self.positions.contains_key(&format!("{base}[{i}]")))
+            .count()
+    }
+
+    /// The length of the longest indexed column group, judging by the highest `[n]` suffix
+    fn max_indexed_len(&self) -> usize {
+        self.positions
+            .keys()
+            .filter_map(|column| {
+                let index = column.strip_suffix(']')?.rsplit_once('[')?.1;
+                index.parse::<usize>().ok()
+            })
+            .map(|index| index + 1)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Deserializes a record struct from one sheet row, matching struct fields to columns by name.
+///
+/// A field renamed to `Name[]` collects the indexed columns `Name[0]`, `Name[1]`, ... into a
+/// `Vec` or fixed-size array. If the element type is a struct, each of its fields `Field` reads
+/// the column `Field[i]`, so columns that share an index can be grouped together:
+///
+/// ```ignore
+/// #[serde(rename = "Item{Ingredient}[]")]
+/// ingredients: Vec<IngredientRecord>, // Item{Ingredient}[i] and Amount{Ingredient}[i]
+/// ```
+///
+/// A field renamed to `*` collects every named column into a map of column names to their
+/// text, for columns that can only be named once other sheets are read.
+#[derive(Clone, Copy)]
+pub struct RowDeserializer<'a> {
+    columns: &'a Columns,
+    source: Source<'a>,
+}
+
+#[derive(Clone, Copy)]
+enum Source<'a> {
+    Row(&'a csv::StringRecord),
+    /// Records each column that's read and yields default values, to find a record's columns
+    Probe(&'a RefCell<Vec<String>>),
+}
+
+impl<'a> RowDeserializer<'a> {
+    pub fn new(columns: &'a Columns, row: &'a csv::StringRecord) -> Self {
+        RowDeserializer {
+            columns,
+            source: Source::Row(row),
+        }
+    }
+
+    fn cell(self, column: String) -> Cell<'a> {
+        match self.source {
+            Source::Row(row) => match self.columns.positions.get(&column) {
+                Some(&position) => Cell::Value {
+                    value: row.get(position).unwrap_or_default(),
+                    column,
+                },
+                None => Cell::Missing { column },
+            },
+            Source::Probe(seen) => {
+                seen.borrow_mut().push(column);
+                Cell::Probe
+            }
+        }
+    }
+
+    fn indexed_len(self, base: &str) -> Result<usize, Error> {
+        let len = self.columns.indexed_len(base);
+        if len > 0 {
+            return Ok(len);
+        }
+        match self.source {
+            // a group whose columns were all renamed is probed as long as the sheet's longest
+            // group, so every one of its missing columns is reported
+            Source::Probe(_) => Ok(self.columns.max_indexed_len().max(1)),
+            Source::Row(_) => Err(missing_column(&format!("{base}[0]"))),
+        }
+    }
+}
+
+/// Lists every column that a record type reads, given the columns that a sheet has
+pub fn record_columns<T: de::DeserializeOwned>(columns: &Columns) -> Vec<String> {
+    let seen = RefCell::new(vec![]);
+    // errors just mean the record type isn't a struct, or uses unsupported attributes
+    let _ = T::deserialize(RowDeserializer {
+        columns,
+        source: Source::Probe(&seen),
+    });
+    seen.into_inner()
+}
+
+fn missing_column(column: &str) -> Error {
+    de::Error::custom(format!("missing field `{column}`"))
+}
+
+fn invalid_cell(column: &str, error: impl Display) -> Error {
+    de::Error::custom(format!("column `{column}`: {error}"))
+}
+
+impl<'de> de::Deserializer<'de> for RowDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+        Err(de::Error::custom("sheet records must be structs"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_map(StructAccess {
+            row: self,
+            fields: fields.iter(),
+            field: None,
+            index: None,
+        })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+struct StructAccess<'a> {
+    row: RowDeserializer<'a>,
+    fields: std::slice::Iter<'static, &'static str>,
+    field: Option<&'static str>,
+    /// Set when deserializing one element of an indexed column group
+    index: Option<usize>,
+}
+
+impl<'de> MapAccess<'de> for StructAccess<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        let Some(&field) = self.fields.next() else {
+            return Ok(None);
+        };
+        self.field = Some(field);
+        seed.deserialize(field.into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let field = self.field.take().expect("value requested before key");
+
+        if field == ALL_COLUMNS {
+            return seed.deserialize(AllColumns { row: self.row });
+        }
+
+        if let Some(base) = field.strip_suffix("[]") {
+            if self.index.is_some() {
+                return Err(de::Error::custom(format!(
+                    "indexed column group `{field}` can't be nested"
+                )));
+            }
+            return seed.deserialize(IndexedColumns {
+                row: self.row,
+                base,
+                len: self.row.indexed_len(base)?,
+            });
+        }
+
+        let column = match self.index {
+            Some(index) => format!("{field}[{index}]"),
+            None => String::from(field),
+        };
+        seed.deserialize(self.row.cell(column))
+    }
+}
+
+/// The field name that collects every column
+const ALL_COLUMNS: &str = "*";
+
+/// Every named column of a row, as a map. Probing yields an empty map, since the columns
+/// that will be read aren't known yet.
+struct AllColumns<'a> {
+    row: RowDeserializer<'a>,
+}
+
+impl<'de> de::Deserializer<'de> for AllColumns<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let cells: Vec<(&str, &str)> = match self.row.source {
+            Source::Row(row) => self
+                .row
+                .columns
+                .positions
+                .iter()
+                .map(|(column, &position)| (column.as_str(), row.get(position).unwrap_or_default()))
+                .collect(),
+            Source::Probe(_) => vec![],
+        };
+        visitor.visit_map(MapDeserializer::new(cells.into_iter()))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// The `{base}[0]`, `{base}[1]`, ... columns of a row, as a sequence
+struct IndexedColumns<'a> {
+    row: RowDeserializer<'a>,
+    base: &'static str,
+    len: usize,
+}
+
+impl<'de> de::Deserializer<'de> for IndexedColumns<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(IndexedAccess {
+            row: self.row,
+            base: self.base,
+            indices: 0..self.len,
+        })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct IndexedAccess<'a> {
+    row: RowDeserializer<'a>,
+    base: &'static str,
+    indices: std::ops::Range<usize>,
+}
+
+impl<'de> SeqAccess<'de> for IndexedAccess<'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        let Some(index) = self.indices.next() else {
+            return Ok(None);
+        };
+        seed.deserialize(IndexedElement {
+            row: self.row,
+            base: self.base,
+            index,
+        })
+        .map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.indices.len())
+    }
+}
+
+/// One element of an indexed column group: either the single column `{base}[index]`, or a
+/// struct whose fields each read `{field}[index]`
+struct IndexedElement<'a> {
+    row: RowDeserializer<'a>,
+    base: &'static str,
+    index: usize,
+}
+
+impl<'a> IndexedElement<'a> {
+    fn cell(self) -> Cell<'a> {
+        self.row.cell(format!("{}[{}]", self.base, self.index))
+    }
+}
+
+macro_rules! forward_to_cell {
+    ($($method:ident)*) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            de::Deserializer::$method(self.cell(), visitor)
+        }
+    )*};
+}
+
+impl<'de> de::Deserializer<'de> for IndexedElement<'de> {
+    type Error = Error;
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_map(StructAccess {
+            row: self.row,
+            fields: fields.iter(),
+            field: None,
+            index: Some(self.index),
+        })
+    }
+
+    forward_to_cell! {
+        deserialize_any deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32
+        deserialize_i64 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_f32 deserialize_f64 deserialize_char deserialize_str deserialize_string
+        deserialize_option
+    }
+
+    serde::forward_to_deserialize_any! {
+        i128 u128 bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// A single cell, parsed from its text
+enum Cell<'a> {
+    Value { value: &'a str, column: String },
+    Missing { column: String },
+    Probe,
+}
+
+macro_rules! parse_cell {
+    ($($method:ident => $visit:ident,)*) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            match self {
+                Cell::Value { value, column } => {
+                    visitor.$visit(value.trim().parse().map_err(|e| invalid_cell(&column, e))?)
+                }
+                Cell::Missing { column } => Err(missing_column(&column)),
+                Cell::Probe => visitor.$visit(Default::default()),
+            }
+        }
+    )*};
+}
+
+impl<'de> de::Deserializer<'de> for Cell<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            Cell::Value { value, .. } => visitor.visit_borrowed_str(value),
+            Cell::Missing { column } => Err(missing_column(&column)),
+            Cell::Probe => visitor.visit_borrowed_str(""),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            Cell::Value { value, column } => match value.trim().to_lowercase().as_str() {
+                "true" => visitor.visit_bool(true),
+                "false" => visitor.visit_bool(false),
+                _ => Err(invalid_cell(&column, "invalid boolean string")),
+            },
+            Cell::Missing { column } => Err(missing_column(&column)),
+            Cell::Probe => visitor.visit_bool(false),
+        }
+    }
+
+    parse_cell! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            Cell::Value { value: "", .. } => visitor.visit_none(),
+            Cell::Value { .. } | Cell::Probe => visitor.visit_some(self),
+            Cell::Missing { .. } => visitor.visit_none(),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        i128 u128 char str string bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::error::Error as SheetError;
+    use crate::utils::read_csv_data;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Ingredient {
+        #[serde(rename = "Item{Ingredient}")]
+        item: i32,
+        #[serde(rename = "Amount{Ingredient}")]
+        amount: u32,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Recipe {
+        #[serde(rename = "#")]
+        id: u32,
+        #[serde(rename = "Item{Ingredient}[]")]
+        ingredients: Vec<Ingredient>,
+        #[serde(rename = "CanHq")]
+        can_hq: bool,
+    }
+
+    const HEADERS: [&str; 6] = [
+        "#",
+        "Item{Ingredient}[0]",
+        "Amount{Ingredient}[0]",
+        "Item{Ingredient}[1]",
+        "Amount{Ingredient}[1]",
+        "CanHq",
+    ];
+
+    fn deserialize<T: de::DeserializeOwned>(headers: &[&str], row: &[&str]) -> Result<T, Error> {
+        let columns = Columns::new(headers.iter().copied());
+        T::deserialize(RowDeserializer::new(
+            &columns,
+            &csv::StringRecord::from(row),
+        ))
+    }
+
+    /// Writes a sheet to a file in the given directory
+    fn sheet_file(dir: &tempfile::TempDir, name: &str, contents: &str) -> PathBuf {
+        let path = dir.path().join(format!("{name}.csv"));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn groups_indexed_columns() {
+        let recipe: Recipe =
+            deserialize(&HEADERS, &["7", "5056", "2", "5107", "1", "True"]).unwrap();
+        assert_eq!(
+            recipe,
+            Recipe {
+                id: 7,
+                ingredients: vec![
+                    Ingredient {
+                        item: 5056,
+                        amount: 2
+                    },
+                    Ingredient {
+                        item: 5107,
+                        amount: 1
+                    },
+                ],
+                can_hq: true,
+            }
+        );
+    }
+
+    #[test]
+    fn reports_missing_groups_and_columns() {
+        let headers = ["#", "Item[0]", "Amount[0]", "CanHq"];
+        let error = deserialize::<Recipe>(&headers, &["7", "5056", "2", "True"]).unwrap_err();
+        assert_eq!(error.to_string(), "missing field `Item{Ingredient}[0]`");
+
+        let headers = ["#", "Item{Ingredient}[0]", "CanHq"];
+        let error = deserialize::<Recipe>(&headers, &["7", "5056", "True"]).unwrap_err();
+        assert_eq!(error.to_string(), "missing field `Amount{Ingredient}[0]`");
+
+        let error = deserialize::<Recipe>(&HEADERS, &["7", "x", "2", "0", "0", "True"]);
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "column `Item{Ingredient}[0]`: invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn collects_every_column() {
+        #[derive(Deserialize)]
+        struct Lookup {
+            #[serde(rename = "#")]
+            id: u32,
+            #[serde(rename = "*")]
+            columns: HashMap<String, String>,
+        }
+
+        let lookup: Lookup = deserialize(&["#", "", "CRP", "BSM"], &["5", "x", "31", "0"]).unwrap();
+        assert_eq!(lookup.id, 5);
+        assert_eq!(
+            lookup.columns,
+            HashMap::from([
+                (String::from("#"), String::from("5")),
+                (String::from("CRP"), String::from("31")),
+                (String::from("BSM"), String::from("0")),
+            ])
+        );
+
+        // the columns aren't known until they're read
+        assert_eq!(record_columns::<Lookup>(&Columns::new(["#", "CRP"])), ["#"]);
+    }
+
+    #[test]
+    fn probes_every_column() {
+        assert_eq!(record_columns::<Recipe>(&Columns::new(HEADERS)), HEADERS);
+
+        // a renamed group is probed as long as the sheet's longest group
+        let columns = Columns::new(["#", "Item[0]", "Amount[0]", "Item[1]", "Amount[1]", "CanHq"]);
+        assert_eq!(record_columns::<Recipe>(&columns), HEADERS);
+
+        let columns = Columns::new(["#"]);
+        assert_eq!(
+            record_columns::<Recipe>(&columns),
+            ["#", "Item{Ingredient}[0]", "Amount{Ingredient}[0]", "CanHq"]
+        );
+    }
+
+    #[test]
+    fn reads_raw_sheet_headers() {
+        let dir = tempfile::tempdir().unwrap();
+        let raw = sheet_file(
+            &dir,
+            "raw",
+            "key,0,1,2,3,4\n\
+             #,Item{Ingredient}[0],Amount{Ingredient}[0],Item{Ingredient}[1],Amount{Ingredient}[1],CanHq\n\
+             int32,Item,byte,Item,byte,bool\n\
+             1,5056,2,0,0,False\n\
+             2,x,1,0,0,False\n",
+        );
+        let error = read_csv_data::<Recipe>(&raw).unwrap_err();
+        // lines count the three header rows
+        assert!(matches!(error, SheetError::BadRow { line: Some(5), .. }));
+
+        let plain = sheet_file(
+            &dir,
+            "plain",
+            "\u{feff}#,Item{Ingredient}[0],Amount{Ingredient}[0],CanHq\n3,5056,2,True\n",
+        );
+        let recipes = read_csv_data::<Recipe>(&plain).unwrap();
+        assert_eq!(recipes[0].id, 3);
+        assert_eq!(recipes[0].ingredients.len(), 1);
+
+        let missing = sheet_file(&dir, "missing", "#,CanHq\n3,True\n");
+        assert!(matches!(
+            read_csv_data::<Recipe>(&missing),
+            Err(SheetError::MissingColumn { column, .. }) if column == "Item{Ingredient}[0]"
+        ));
+    }
+}
@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::lookup::normalize_name;
+use crate::recipes::RecipeOutput;
+use crate::translated_items::{language_tag, ItemTranslations};
+use crate::utils::write_json_file;
+
+/// Length of the n-grams that names are indexed by. Bigrams keep Japanese names, which are
+/// often only a few characters long, searchable.
+pub const GRAM_SIZE: usize = 2;
+
+/// A search index over item names in every language, to ship alongside recipes.json
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchIndex {
+    /// Language tags, in the order of each item's names
+    pub languages: Vec<String>,
+    pub items: Vec<SearchItem>,
+    /// Each n-gram of the normalized names, mapped to the positions in `items` of the items
+    /// whose names contain it
+    pub grams: BTreeMap<String, Vec<u32>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchItem {
+    pub id: u32,
+    /// Normalized names, in the order of [`SearchIndex::languages`]. A name is empty if
+    /// there's no translation.
+    pub names: Vec<String>,
+    /// Positions in recipes.json of the recipes that make the item
+    pub recipes: Vec<u32>,
+}
+
+/// How well a name matches a query, best first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchRank {
+    Exact,
+    Prefix,
+    /// A word in the name starts with the query
+    WordPrefix,
+    Substring,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchHit<'a> {
+    pub item: &'a SearchItem,
+    /// Tag of the language whose name matched best
+    pub language: &'a str,
+    /// The normalized name that matched
+    pub name: &'a str,
+    pub rank: MatchRank,
+}
+
+pub fn build_search_index(recipes: &[RecipeOutput], items: &ItemTranslations) -> SearchIndex {
+    let languages: BTreeSet<&str> = items
+        .values()
+        .flat_map(|names| names.translations.keys().map(String::as_str))
+        .collect();
+
+    let mut recipes_by_item: HashMap<u32, Vec<u32>> = HashMap::new();
+    for (position, recipe) in recipes.iter().enumerate() {
+        recipes_by_item
+            .entry(recipe.item_id)
+            .or_default()
+            .push(position as u32);
+    }
+
+    let mut index = SearchIndex {
+        languages: std::iter::once("en")
+            .chain(languages.iter().map(|language| language_tag(language)))
+            .map(String::from)
+            .collect(),
+        ..SearchIndex::default()
+    };
+
+    let mut grams: BTreeMap<String, BTreeSet<u32>> = BTreeMap::new();
+    for (id, item_names) in items {
+        let position = index.items.len() as u32;
+
+        let mut names = vec![normalize_name(&item_names.english)];
+        for language in &languages {
+            let name = item_names.translations.get(*language);
+            names.push(name.map(|name| normalize_name(name)).unwrap_or_default());
+        }
+
+        for name in &names {
+            for gram in name_grams(name) {
+                grams.entry(gram).or_default().insert(position);
+            }
+        }
+
+        index.items.push(SearchItem {
+            id: *id,
+            names,
+            recipes: recipes_by_item.remove(id).unwrap_or_default(),
+        });
+    }
+
+    index.grams = grams
+        .into_iter()
+        .map(|(gram, positions)| (gram, positions.into_iter().collect()))
+        .collect();
+    index
+}
+
+impl SearchIndex {
+    pub fn read(path: impl AsRef<Path>) -> Result<SearchIndex> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|e| Error::io(path, e))?;
+        serde_json::from_reader(BufReader::new(file)).map_err(|e| Error::io(path, e))
+    }
+
+    /// Items with a name in any language that contains the query, best matches first. The
+    /// query is normalized like the names are.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchHit<'_>> {
+        let query = normalize_name(query);
+        if query.is_empty() {
+            return vec![];
+        }
+
+        let mut hits: Vec<SearchHit> = self
+            .candidates(&query)
+            .into_iter()
+            .filter_map(|position| {
+                let item = &self.items[position as usize];
+                item.names
+                    .iter()
+                    .zip(&self.languages)
+                    .filter_map(|(name, language)| {
+                        let rank = match_rank(name, &query)?;
+                        Some(SearchHit {
+                            item,
+                            language,
+                            name,
+                            rank,
+                        })
+                    })
+                    .min_by_key(|hit| hit.rank)
+            })
+            .collect();
+
+        hits.sort_by_key(|hit| (hit.rank, hit.name.chars().count(), hit.item.id));
+        hits.truncate(limit);
+        hits
+    }
+
+    /// Positions of the items that have every n-gram of the query
+    fn candidates(&self, query: &str) -> Vec<u32> {
+        let mut postings = vec![];
+        for gram in name_grams(query) {
+            match self.grams.get(&gram) {
+                Some(positions) => postings.push(positions),
+                None => return vec![],
+            }
+        }
+        postings.sort_by_key(|positions| positions.len());
+
+        let Some((shortest, rest)) = postings.split_first() else {
+            return (0..self.items.len() as u32).collect();
+        };
+        let mut candidates = (*shortest).clone();
+        for positions in rest {
+            candidates.retain(|position| positions.binary_search(position).is_ok());
+        }
+        candidates
+    }
+}
+
+fn match_rank(name: &str, query: &str) -> Option<MatchRank> {
+    if name == query {
+        Some(MatchRank::Exact)
+    } else if name.starts_with(query) {
+        Some(MatchRank::Prefix)
+    } else if name.split(' ').any(|word| word.starts_with(query)) {
+        Some(MatchRank::WordPrefix)
+    } else if name.contains(query) {
+        Some(MatchRank::Substring)
+    } else {
+        None
+    }
+}
+
+/// A name's n-grams. Names shorter than an n-gram have none, so a query that short is
+/// checked against every item.
+fn name_grams(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    if chars.len() < GRAM_SIZE {
+        return vec![];
+    }
+    chars
+        .windows(GRAM_SIZE)
+        .map(|gram| gram.iter().collect())
+        .collect()
+}
+
+/// Writes `search_index.json`
+pub fn write_search_index(index: &SearchIndex, output_dir: &Path) -> Result<()> {
+    write_json_file(index, output_dir.join("search_index.json"))
+}
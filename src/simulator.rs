@@ -0,0 +1,489 @@
+//! A deterministic crafting simulator. A craft starts from a recipe and the crafter's stats,
+//! and each action produces a new state. Success rates and conditions are rolled with a
+//! seeded RNG, so the same seed and actions always produce the same craft.
+
+mod action;
+mod rng;
+
+pub use action::{Action, ActionParams, ActionTable, Potency};
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::conditions::{Condition, Conditions};
+use crate::formulas::{base_values, BaseValues};
+use crate::recipes::RecipeOutput;
+use rng::Rng;
+
+// Inner Quiet is a trait from this level onwards
+const INNER_QUIET_LEVEL: u32 = 11;
+const MAX_INNER_QUIET: u32 = 10;
+// Hasty Touch grants Expedience from this level onwards
+const EXPEDIENCE_LEVEL: u32 = 96;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrafterStats {
+    pub level: u32,
+    pub craftsmanship: u32,
+    pub control: u32,
+    pub cp: u32,
+    pub is_specialist: bool,
+}
+
+/// Everything about a craft that doesn't change between steps
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CraftSettings {
+    pub crafter: CrafterStats,
+    pub job_level: u32,
+    pub max_progress: u32,
+    pub max_quality: u32,
+    pub max_durability: u32,
+    pub is_expert: bool,
+    pub conditions: Conditions,
+    pub base: BaseValues,
+    /// Unlock levels, CP costs and potency of the actions
+    pub actions: Arc<ActionTable>,
+}
+
+impl CraftSettings {
+    pub fn new(recipe: &RecipeOutput, crafter: CrafterStats, actions: Arc<ActionTable>) -> Self {
+        CraftSettings {
+            crafter,
+            job_level: recipe.job_level,
+            max_progress: recipe.progress,
+            max_quality: recipe.quality,
+            max_durability: recipe.durability,
+            is_expert: recipe.is_expert,
+            conditions: recipe.conditions,
+            base: base_values(
+                recipe,
+                crafter.level,
+                crafter.craftsmanship,
+                crafter.control,
+            ),
+            actions,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Buff {
+    InnerQuiet,
+    WasteNot,
+    Veneration,
+    GreatStrides,
+    Innovation,
+    FinalAppraisal,
+    MuscleMemory,
+    Manipulation,
+    HeartAndSoul,
+    Expedience,
+    TrainedPerfection,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActiveBuff {
+    pub stacks: u32,
+    /// Steps left, or `None` if the buff lasts until it's used
+    pub duration: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    InProgress,
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ActionError {
+    #[error("the craft is already finished")]
+    Finished,
+
+    #[error("{action} isn't in the action data")]
+    Unavailable { action: Action },
+
+    #[error("{action} is learned at level {level}")]
+    LevelTooLow { action: Action, level: u32 },
+
+    #[error("{action} costs {cost} CP, but only {cp} CP is left")]
+    NotEnoughCp { action: Action, cost: u32, cp: u32 },
+
+    #[error("{action} {reason}")]
+    Unusable {
+        action: Action,
+        reason: &'static str,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CraftState {
+    pub settings: CraftSettings,
+    /// Number of steps taken so far
+    pub step: u32,
+    pub progress: u32,
+    pub quality: u32,
+    pub durability: u32,
+    pub cp: u32,
+    pub condition: Condition,
+    pub buffs: BTreeMap<Buff, ActiveBuff>,
+    /// The previous action, if it can start or continue a touch combo
+    combo: Option<Action>,
+    /// How many times each action with limited uses has been used
+    used: BTreeMap<Action, u32>,
+    rng: Rng,
+}
+
+impl CraftState {
+    pub fn new(settings: CraftSettings, seed: u64) -> Self {
+        CraftState {
+            step: 0,
+            progress: 0,
+            quality: 0,
+            durability: settings.max_durability,
+            cp: settings.crafter.cp,
+            settings,
+            condition: Condition::Normal,
+            buffs: BTreeMap::new(),
+            combo: None,
+            used: BTreeMap::new(),
+            rng: Rng::new(seed),
+        }
+    }
+
+    pub fn status(&self) -> Status {
+        if self.progress >= self.settings.max_progress {
+            Status::Completed
+        } else if self.durability == 0 {
+            Status::Failed
+        } else {
+            Status::InProgress
+        }
+    }
+
+    pub fn buff(&self, buff: Buff) -> Option<ActiveBuff> {
+        self.buffs.get(&buff).copied()
+    }
+
+    pub fn inner_quiet(&self) -> u32 {
+        self.buff(Buff::InnerQuiet).map_or(0, |buff| buff.stacks)
+    }
+
+    /// CP that the action would cost in the current state, or `None` if the action isn't in
+    /// the action data
+    pub fn cp_cost(&self, action: Action) -> Option<u32> {
+        let cost = match (action, self.combo) {
+            (Action::StandardTouch, Some(Action::BasicTouch))
+            | (Action::AdvancedTouch, Some(Action::StandardTouch | Action::Observe)) => 18,
+            _ => self.settings.actions.get(action)?.cp_cost,
+        };
+        let multiplier = self.condition.effect().cp_cost_multiplier;
+        Some((cost * multiplier).div_ceil(100))
+    }
+
+    /// Durability that the action would cost in the current state
+    pub fn durability_cost(&self, action: Action) -> u32 {
+        let mut cost = action.durability_cost();
+        if cost > 0 && self.buffs.contains_key(&Buff::TrainedPerfection) {
+            return 0;
+        }
+        if self.buffs.contains_key(&Buff::WasteNot) {
+            cost = cost.div_ceil(2);
+        }
+        (cost * self.condition.effect().durability_cost_multiplier).div_ceil(100)
+    }
+
+    /// Uses an action, returning the state after it, or an error if it can't be used now
+    pub fn apply(&self, action: Action) -> Result<CraftState, ActionError> {
+        self.check(action)?;
+        let mut next = self.clone();
+        next.execute(action);
+        Ok(next)
+    }
+
+    fn check(&self, action: Action) -> Result<(), ActionError> {
+        let unusable = |reason| Err(ActionError::Unusable { action, reason });
+        let crafter = &self.settings.crafter;
+
+        if self.status() != Status::InProgress {
+            return Err(ActionError::Finished);
+        }
+        let Some(params) = self.settings.actions.get(action) else {
+            return Err(ActionError::Unavailable { action });
+        };
+        if crafter.level < params.level {
+            return Err(ActionError::LevelTooLow {
+                action,
+                level: params.level,
+            });
+        }
+        if params.is_specialist && !crafter.is_specialist {
+            return unusable("can only be used by specialists");
+        }
+        if let Some(uses) = action.uses_per_craft() {
+            if self.used.get(&action).copied().unwrap_or(0) >= uses {
+                return unusable("has no uses left this craft");
+            }
+        }
+        if action.is_opener() && self.step > 0 {
+            return unusable("can only be used on the first step");
+        }
+        if action.requires_good_condition()
+            && !matches!(self.condition, Condition::Good | Condition::Excellent)
+            && !self.buffs.contains_key(&Buff::HeartAndSoul)
+        {
+            return unusable("requires a Good or Excellent condition");
+        }
+
+        match action {
+            Action::ByregotsBlessing if self.inner_quiet() == 0 => {
+                return unusable("requires Inner Quiet");
+            }
+            Action::TrainedFinesse if self.inner_quiet() < MAX_INNER_QUIET => {
+                return unusable("requires 10 stacks of Inner Quiet");
+            }
+            Action::PrudentTouch | Action::PrudentSynthesis
+                if self.buffs.contains_key(&Buff::WasteNot) =>
+            {
+                return unusable("can't be used during Waste Not");
+            }
+            Action::TrainedEye
+                if self.settings.is_expert || crafter.level < self.settings.job_level + 10 =>
+            {
+                return unusable("requires a non-expert recipe 10 levels below the crafter");
+            }
+            Action::DaringTouch if !self.buffs.contains_key(&Buff::Expedience) => {
+                return unusable("requires Expedience");
+            }
+            Action::QuickInnovation if self.buffs.contains_key(&Buff::Innovation) => {
+                return unusable("can't be used during Innovation");
+            }
+            _ => {}
+        }
+
+        let cost = self.cp_cost(action).unwrap_or(params.cp_cost);
+        if cost > self.cp {
+            return Err(ActionError::NotEnoughCp {
+                action,
+                cost,
+                cp: self.cp,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn execute(&mut self, action: Action) {
+        let effect = self.condition.effect();
+        let settings = self.settings.clone();
+        let level = settings.crafter.level;
+
+        self.cp -= self.cp_cost(action).unwrap_or(0);
+        if action.uses_per_craft().is_some() {
+            *self.used.entry(action).or_insert(0) += 1;
+        }
+
+        // Careful Observation changes the condition without taking a step
+        if action == Action::CarefulObservation {
+            self.condition = self.next_condition();
+            return;
+        }
+
+        let durability_cost = self.durability_cost(action);
+
+        let potency = settings.actions.potency(action, level);
+        let success_rate = potency.success_rate + effect.success_rate_bonus;
+        let success = success_rate >= 100 || self.rng.next_f64() * 100.0 < f64::from(success_rate);
+
+        if success {
+            let mut efficiency = potency.progress;
+            // Groundwork is half as effective without enough durability for it
+            if action == Action::Groundwork && self.durability < durability_cost {
+                efficiency /= 2;
+            }
+            if efficiency > 0 {
+                self.add_progress(efficiency, effect.progress_multiplier);
+            }
+
+            let mut efficiency = potency.quality;
+            // Byregot's Blessing gains 20% for each stack of Inner Quiet
+            if action == Action::ByregotsBlessing {
+                efficiency += 20 * self.inner_quiet();
+            }
+            if efficiency > 0 {
+                self.add_quality(efficiency, effect.quality_multiplier);
+                self.gain_inner_quiet(action);
+            }
+        }
+
+        match action {
+            Action::TrainedEye => self.quality = settings.max_quality,
+            Action::MastersMend => {
+                self.durability = (self.durability + 30).min(settings.max_durability);
+            }
+            Action::ImmaculateMend => self.durability = settings.max_durability,
+            Action::TricksOfTheTrade => self.cp = (self.cp + 20).min(settings.crafter.cp),
+            Action::ByregotsBlessing => {
+                self.buffs.remove(&Buff::InnerQuiet);
+            }
+            _ => {}
+        }
+
+        if action.durability_cost() > 0 {
+            self.buffs.remove(&Buff::TrainedPerfection);
+        }
+        if action == Action::DaringTouch {
+            self.buffs.remove(&Buff::Expedience);
+        }
+        if action.requires_good_condition()
+            && !matches!(self.condition, Condition::Good | Condition::Excellent)
+        {
+            self.buffs.remove(&Buff::HeartAndSoul);
+        }
+        self.durability = self.durability.saturating_sub(durability_cost);
+
+        self.combo = match (action, self.combo) {
+            (Action::BasicTouch | Action::Observe, _) => Some(action),
+            (Action::StandardTouch, Some(Action::BasicTouch)) => Some(action),
+            _ => None,
+        };
+
+        if action.is_free() {
+            self.add_buffs(action, success, effect.buff_duration_bonus);
+            return;
+        }
+
+        self.step += 1;
+        if self.status() != Status::InProgress {
+            return;
+        }
+
+        if action != Action::Manipulation && self.buffs.contains_key(&Buff::Manipulation) {
+            self.durability = (self.durability + 5).min(settings.max_durability);
+        }
+        self.tick_buffs();
+        self.add_buffs(action, success, effect.buff_duration_bonus);
+        self.condition = self.next_condition();
+    }
+
+    fn add_progress(&mut self, efficiency: u32, condition_multiplier: u32) {
+        let mut buff_multiplier = 100;
+        if self.buffs.contains_key(&Buff::Veneration) {
+            buff_multiplier += 50;
+        }
+        if self.buffs.remove(&Buff::MuscleMemory).is_some() {
+            buff_multiplier += 100;
+        }
+
+        let increase = u64::from(self.settings.base.progress)
+            * u64::from(efficiency)
+            * u64::from(condition_multiplier)
+            * buff_multiplier
+            / 1_000_000;
+        self.progress += increase as u32;
+
+        // Final Appraisal stops the craft from completing, once
+        if self.progress >= self.settings.max_progress
+            && self.buffs.remove(&Buff::FinalAppraisal).is_some()
+        {
+            self.progress = self.settings.max_progress - 1;
+        }
+    }
+
+    fn add_quality(&mut self, efficiency: u32, condition_multiplier: u32) {
+        let mut buff_multiplier = 100;
+        if self.buffs.contains_key(&Buff::Innovation) {
+            buff_multiplier += 50;
+        }
+        if self.buffs.remove(&Buff::GreatStrides).is_some() {
+            buff_multiplier += 100;
+        }
+        // each stack of Inner Quiet adds 10%
+        let inner_quiet_multiplier = u64::from(10 + self.inner_quiet());
+
+        let increase = u64::from(self.settings.base.quality)
+            * u64::from(efficiency)
+            * u64::from(condition_multiplier)
+            * buff_multiplier
+            * inner_quiet_multiplier
+            / 10_000_000;
+        self.quality = (self.quality + increase as u32).min(self.settings.max_quality);
+    }
+
+    fn gain_inner_quiet(&mut self, action: Action) {
+        if self.settings.crafter.level < INNER_QUIET_LEVEL {
+            return;
+        }
+        let stacks = match (action, self.combo) {
+            (Action::PreciseTouch | Action::PreparatoryTouch | Action::Reflect, _)
+            | (Action::RefinedTouch, Some(Action::BasicTouch)) => 2,
+            _ => 1,
+        };
+        let inner_quiet = self.buffs.entry(Buff::InnerQuiet).or_insert(ActiveBuff {
+            stacks: 0,
+            duration: None,
+        });
+        inner_quiet.stacks = (inner_quiet.stacks + stacks).min(MAX_INNER_QUIET);
+    }
+
+    fn tick_buffs(&mut self) {
+        self.buffs.retain(|_, buff| match &mut buff.duration {
+            Some(duration) => {
+                *duration -= 1;
+                *duration > 0
+            }
+            None => true,
+        });
+    }
+
+    fn add_buffs(&mut self, action: Action, success: bool, duration_bonus: u32) {
+        let (buff, duration) = match action {
+            Action::WasteNot => (Buff::WasteNot, Some(4)),
+            Action::WasteNotII => (Buff::WasteNot, Some(8)),
+            Action::Veneration => (Buff::Veneration, Some(4)),
+            Action::GreatStrides => (Buff::GreatStrides, Some(3)),
+            Action::Innovation => (Buff::Innovation, Some(4)),
+            Action::QuickInnovation => (Buff::Innovation, Some(1)),
+            Action::FinalAppraisal => (Buff::FinalAppraisal, Some(5)),
+            Action::MuscleMemory => (Buff::MuscleMemory, Some(5)),
+            Action::Manipulation => (Buff::Manipulation, Some(8)),
+            Action::HeartAndSoul => (Buff::HeartAndSoul, None),
+            Action::TrainedPerfection => (Buff::TrainedPerfection, None),
+            Action::HastyTouch if success && self.settings.crafter.level >= EXPEDIENCE_LEVEL => {
+                // Expedience is a trait rather than a buff action, so Primed doesn't extend it
+                self.buffs.insert(
+                    Buff::Expedience,
+                    ActiveBuff {
+                        stacks: 0,
+                        duration: Some(1),
+                    },
+                );
+                return;
+            }
+            _ => return,
+        };
+        self.buffs.insert(
+            buff,
+            ActiveBuff {
+                stacks: 0,
+                duration: duration.map(|duration| duration + duration_bonus),
+            },
+        );
+    }
+
+    fn next_condition(&mut self) -> Condition {
+        if let Some(condition) = self.condition.next() {
+            return condition;
+        }
+
+        let chances = self.settings.conditions.chances(self.settings.is_expert);
+        let mut roll = self.rng.next_f64();
+        for (condition, chance) in chances {
+            if roll < chance {
+                return condition;
+            }
+            roll -= chance;
+        }
+        Condition::Normal
+    }
+}
@@ -0,0 +1,329 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use crate::craft_actions::CraftActionOutput;
+use crate::error::{Error, Result};
+
+/// A crafting action, with the mechanics it has as of patch 7.x. Unlock levels, CP costs,
+/// efficiencies and success rates come from an [`ActionTable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Action {
+    BasicSynthesis,
+    BasicTouch,
+    MastersMend,
+    HastyTouch,
+    RapidSynthesis,
+    Observe,
+    TricksOfTheTrade,
+    WasteNot,
+    Veneration,
+    StandardTouch,
+    GreatStrides,
+    Innovation,
+    FinalAppraisal,
+    WasteNotII,
+    ByregotsBlessing,
+    PreciseTouch,
+    MuscleMemory,
+    CarefulObservation,
+    CarefulSynthesis,
+    Manipulation,
+    PrudentTouch,
+    AdvancedTouch,
+    Reflect,
+    PreparatoryTouch,
+    Groundwork,
+    DelicateSynthesis,
+    IntensiveSynthesis,
+    TrainedEye,
+    HeartAndSoul,
+    PrudentSynthesis,
+    TrainedFinesse,
+    RefinedTouch,
+    DaringTouch,
+    QuickInnovation,
+    ImmaculateMend,
+    TrainedPerfection,
+}
+
+impl Action {
+    pub const ALL: [Action; 36] = [
+        Action::BasicSynthesis,
+        Action::BasicTouch,
+        Action::MastersMend,
+        Action::HastyTouch,
+        Action::RapidSynthesis,
+        Action::Observe,
+        Action::TricksOfTheTrade,
+        Action::WasteNot,
+        Action::Veneration,
+        Action::StandardTouch,
+        Action::GreatStrides,
+        Action::Innovation,
+        Action::FinalAppraisal,
+        Action::WasteNotII,
+        Action::ByregotsBlessing,
+        Action::PreciseTouch,
+        Action::MuscleMemory,
+        Action::CarefulObservation,
+        Action::CarefulSynthesis,
+        Action::Manipulation,
+        Action::PrudentTouch,
+        Action::AdvancedTouch,
+        Action::Reflect,
+        Action::PreparatoryTouch,
+        Action::Groundwork,
+        Action::DelicateSynthesis,
+        Action::IntensiveSynthesis,
+        Action::TrainedEye,
+        Action::HeartAndSoul,
+        Action::PrudentSynthesis,
+        Action::TrainedFinesse,
+        Action::RefinedTouch,
+        Action::DaringTouch,
+        Action::QuickInnovation,
+        Action::ImmaculateMend,
+        Action::TrainedPerfection,
+    ];
+
+    /// The action's English name, as it appears in the CraftAction and Action sheets
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::BasicSynthesis => "Basic Synthesis",
+            Action::BasicTouch => "Basic Touch",
+            Action::MastersMend => "Master's Mend",
+            Action::HastyTouch => "Hasty Touch",
+            Action::RapidSynthesis => "Rapid Synthesis",
+            Action::Observe => "Observe",
+            Action::TricksOfTheTrade => "Tricks of the Trade",
+            Action::WasteNot => "Waste Not",
+            Action::Veneration => "Veneration",
+            Action::StandardTouch => "Standard Touch",
+            Action::GreatStrides => "Great Strides",
+            Action::Innovation => "Innovation",
+            Action::FinalAppraisal => "Final Appraisal",
+            Action::WasteNotII => "Waste Not II",
+            Action::ByregotsBlessing => "Byregot's Blessing",
+            Action::PreciseTouch => "Precise Touch",
+            Action::MuscleMemory => "Muscle Memory",
+            Action::CarefulObservation => "Careful Observation",
+            Action::CarefulSynthesis => "Careful Synthesis",
+            Action::Manipulation => "Manipulation",
+            Action::PrudentTouch => "Prudent Touch",
+            Action::AdvancedTouch => "Advanced Touch",
+            Action::Reflect => "Reflect",
+            Action::PreparatoryTouch => "Preparatory Touch",
+            Action::Groundwork => "Groundwork",
+            Action::DelicateSynthesis => "Delicate Synthesis",
+            Action::IntensiveSynthesis => "Intensive Synthesis",
+            Action::TrainedEye => "Trained Eye",
+            Action::HeartAndSoul => "Heart and Soul",
+            Action::PrudentSynthesis => "Prudent Synthesis",
+            Action::TrainedFinesse => "Trained Finesse",
+            Action::RefinedTouch => "Refined Touch",
+            Action::DaringTouch => "Daring Touch",
+            Action::QuickInnovation => "Quick Innovation",
+            Action::ImmaculateMend => "Immaculate Mend",
+            Action::TrainedPerfection => "Trained Perfection",
+        }
+    }
+
+    /// Looks up an action by its English name
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|action| action.name() == name)
+    }
+
+    /// Durability cost, before buffs and conditions
+    pub fn durability_cost(self) -> u32 {
+        match self {
+            Action::PrudentTouch | Action::PrudentSynthesis => 5,
+            Action::PreparatoryTouch | Action::Groundwork => 20,
+            Action::BasicSynthesis
+            | Action::BasicTouch
+            | Action::HastyTouch
+            | Action::RapidSynthesis
+            | Action::StandardTouch
+            | Action::ByregotsBlessing
+            | Action::PreciseTouch
+            | Action::MuscleMemory
+            | Action::CarefulSynthesis
+            | Action::AdvancedTouch
+            | Action::Reflect
+            | Action::DelicateSynthesis
+            | Action::IntensiveSynthesis
+            | Action::TrainedEye
+            | Action::RefinedTouch
+            | Action::DaringTouch => 10,
+            _ => 0,
+        }
+    }
+
+    /// Whether the action's description calls its progress efficiency just `Efficiency`
+    pub fn is_synthesis(self) -> bool {
+        matches!(
+            self,
+            Action::BasicSynthesis
+                | Action::RapidSynthesis
+                | Action::MuscleMemory
+                | Action::CarefulSynthesis
+                | Action::Groundwork
+                | Action::IntensiveSynthesis
+                | Action::PrudentSynthesis
+        )
+    }
+
+    /// Whether the action needs a Good or Excellent condition, or Heart and Soul
+    pub fn requires_good_condition(self) -> bool {
+        matches!(
+            self,
+            Action::TricksOfTheTrade | Action::PreciseTouch | Action::IntensiveSynthesis
+        )
+    }
+
+    /// Whether the action can only be used on the first step
+    pub fn is_opener(self) -> bool {
+        matches!(
+            self,
+            Action::MuscleMemory | Action::Reflect | Action::TrainedEye
+        )
+    }
+
+    /// How many times the action can be used per craft, if it's limited
+    pub fn uses_per_craft(self) -> Option<u32> {
+        match self {
+            Action::HeartAndSoul | Action::QuickInnovation | Action::TrainedPerfection => Some(1),
+            Action::CarefulObservation => Some(3),
+            _ => None,
+        }
+    }
+
+    /// Whether the action happens outside of the normal step order, so buffs don't tick down
+    /// and the condition doesn't change
+    pub fn is_free(self) -> bool {
+        matches!(
+            self,
+            Action::FinalAppraisal | Action::HeartAndSoul | Action::QuickInnovation
+        )
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The parts of an action that are read from the game data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActionParams {
+    /// The crafter level the action is learned at
+    pub level: u32,
+    /// CP cost, before combos and conditions
+    pub cp_cost: u32,
+    /// Whether the action needs the crafter to be a specialist
+    pub is_specialist: bool,
+}
+
+/// An action's efficiencies and success rate from some level onwards, read from the numbers
+/// in its description
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Potency {
+    /// Progress efficiency as a percentage
+    pub progress: u32,
+    /// Quality efficiency as a percentage, before Byregot's Blessing's Inner Quiet bonus
+    pub quality: u32,
+    /// Chance of success as a percentage, before conditions
+    pub success_rate: u32,
+}
+
+impl Potency {
+    /// For actions without efficiencies, like buffs
+    const NONE: Potency = Potency {
+        progress: 0,
+        quality: 0,
+        success_rate: 100,
+    };
+
+    fn new(action: Action, numbers: &BTreeMap<String, u32>) -> Self {
+        let number = |label: &str| numbers.get(label).copied();
+        let efficiency = number("Efficiency").unwrap_or(0);
+        let (progress, quality) = if action.is_synthesis() {
+            (efficiency, 0)
+        } else {
+            (0, efficiency)
+        };
+        Potency {
+            progress: number("Synthesis Action Efficiency").unwrap_or(progress),
+            quality: number("Touch Action Efficiency").unwrap_or(quality),
+            success_rate: number("Success Rate").unwrap_or(100),
+        }
+    }
+}
+
+/// The parameters of each action the simulator can use, from the crafting action table
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ActionTable {
+    params: BTreeMap<Action, ActionParams>,
+    /// Each action's potency from the levels it changes at, in level order
+    potency: BTreeMap<Action, Vec<(u32, Potency)>>,
+}
+
+impl ActionTable {
+    /// Builds the table from the crafting action table, as written to actions_data.json.
+    /// Crafting actions the simulator doesn't know are left out.
+    pub fn new(craft_actions: &[CraftActionOutput]) -> Self {
+        let mut table = ActionTable::default();
+        for craft_action in craft_actions {
+            let Some(action) = Action::from_name(&craft_action.name) else {
+                continue;
+            };
+            table.params.insert(
+                action,
+                ActionParams {
+                    level: craft_action.level,
+                    cp_cost: craft_action.cp_cost,
+                    is_specialist: craft_action.is_specialist,
+                },
+            );
+            table.potency.insert(
+                action,
+                craft_action
+                    .numbers
+                    .iter()
+                    .map(|(&level, numbers)| (level, Potency::new(action, numbers)))
+                    .collect(),
+            );
+        }
+        table
+    }
+
+    /// Reads the crafting action table from actions_data.json
+    pub fn read(path: impl AsRef<Path>) -> Result<ActionTable> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|e| Error::io(path, e))?;
+        let craft_actions: Vec<CraftActionOutput> =
+            serde_json::from_reader(BufReader::new(file)).map_err(|e| Error::io(path, e))?;
+        Ok(ActionTable::new(&craft_actions))
+    }
+
+    /// The action's parameters, or `None` if it isn't in the game data
+    pub fn get(&self, action: Action) -> Option<ActionParams> {
+        self.params.get(&action).copied()
+    }
+
+    /// The action's potency for a crafter of the given level. Actions without numbers in
+    /// their description, like buffs, have no efficiency and always succeed.
+    pub fn potency(&self, action: Action, level: u32) -> Potency {
+        let levels = self.potency.get(&action).map_or(&[][..], Vec::as_slice);
+        levels
+            .iter()
+            .rev()
+            .find(|&&(from, _)| from <= level)
+            .or(levels.first())
+            .map_or(Potency::NONE, |&(_, potency)| potency)
+    }
+}
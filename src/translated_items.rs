@@ -1,82 +1,415 @@
+use serde::ser::SerializeMap;
+use serde::Serialize;
 use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
 
 use crate::{
-    models::{ActionRecord, CraftActionRecord, ItemRecord},
-    utils::{read_csv_data, write_json_file},
+    error::{Error, Result},
+    game_data::{GameData, SheetTable},
+    icons::status_icon_names,
+    models::{ActionRecord, ClassJobRecord, CraftActionRecord, ItemRecord, Sheet, StatusRecord},
+    utils::write_json_file,
+    validate::SheetSchema,
 };
 
-static LANGUAGES: [&str; 3] = ["jpn", "deu", "fra"];
+/// Languages the game has been localized into, by the name of the data subdirectory that
+/// holds their sheets. The Korean and Chinese clients are usually a patch or two behind.
+pub const LANGUAGES: [&str; 6] = ["jpn", "deu", "fra", "kor", "chs", "cht"];
 
-pub fn build_translated_items(english_items: HashMap<u32, String>) {
-    for language in LANGUAGES {
-        let mut translations = BTreeMap::new();
+/// The BCP 47 tag for a language, as used in id-keyed translation files
+pub fn language_tag(language: &str) -> &str {
+    match language {
+        "jpn" => "ja",
+        "deu" => "de",
+        "fra" => "fr",
+        "kor" => "ko",
+        "chs" => "zh-Hans",
+        "cht" => "zh-Hant",
+        _ => language,
+    }
+}
 
-        let mut non_english_items = HashMap::new();
+/// Sheets that a data subdirectory needs to be used as a language
+pub const LOCALIZED_SHEETS: [&str; 4] = [
+    ItemRecord::NAME,
+    ActionRecord::NAME,
+    CraftActionRecord::NAME,
+    StatusRecord::NAME,
+];
 
-        for item in read_csv_data::<ItemRecord>(format!("data/{language}/Item.csv")) {
-            if item.name.trim().is_empty() {
-                continue;
+/// A data subdirectory that isn't used as a language
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedDir {
+    pub name: String,
+    /// The localized sheet files it doesn't have
+    pub missing: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DetectedLanguages {
+    /// Known languages first, in the order of [`LANGUAGES`], then any others by name
+    pub languages: Vec<String>,
+    pub skipped: Vec<SkippedDir>,
+}
+
+/// Finds the data subdirectories that have every localized sheet
+pub fn detect_languages(data_dir: &Path) -> Result<DetectedLanguages> {
+    let entries = std::fs::read_dir(data_dir).map_err(|error| Error::io(data_dir, error))?;
+
+    let mut detected = DetectedLanguages::default();
+    for entry in entries {
+        let entry = entry.map_err(|error| Error::io(data_dir, error))?;
+        if !entry.path().is_dir() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with('.') {
+            continue;
+        }
+
+        let missing: Vec<String> = LOCALIZED_SHEETS
+            .into_iter()
+            .map(|sheet| format!("{sheet}.csv"))
+            .filter(|file| !entry.path().join(file).is_file())
+            .collect();
+        if missing.is_empty() {
+            detected.languages.push(name);
+        } else {
+            detected.skipped.push(SkippedDir { name, missing });
+        }
+    }
+
+    detected.languages.sort_by_key(|language| {
+        let known = LANGUAGES.iter().position(|known| known == language);
+        (known.unwrap_or(LANGUAGES.len()), language.clone())
+    });
+    detected.skipped.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(detected)
+}
+
+pub fn sheets(languages: &[String]) -> Vec<SheetSchema> {
+    let mut sheets = vec![
+        SheetSchema::of::<ActionRecord>(),
+        SheetSchema::of::<ClassJobRecord>(),
+        SheetSchema::of::<CraftActionRecord>(),
+        SheetSchema::of::<StatusRecord>(),
+    ];
+    for language in languages {
+        sheets.push(SheetSchema::localized::<ItemRecord>(language));
+        sheets.push(SheetSchema::localized::<ActionRecord>(language));
+        sheets.push(SheetSchema::localized::<CraftActionRecord>(language));
+        sheets.push(SheetSchema::localized::<StatusRecord>(language));
+    }
+    sheets
+}
+
+/// English names mapped to their translations, keyed by language
+pub type Translations = BTreeMap<String, BTreeMap<String, String>>;
+
+/// What to do when a localized sheet is missing a row, or the row's name is empty
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum MissingPolicy {
+    /// Fail the run
+    Strict,
+    /// Use the English name
+    Fallback,
+    /// Leave the name out of the translation file
+    #[default]
+    Omit,
+}
+
+/// A row without a translation
+#[derive(Debug, Clone, Serialize)]
+pub struct Untranslated {
+    pub id: u32,
+    pub english_name: String,
+}
+
+/// How much of one sheet a language has translations for
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SheetCoverage {
+    pub total: usize,
+    pub translated: usize,
+    /// Rows that the localized sheet doesn't have
+    pub missing: Vec<Untranslated>,
+    /// Rows that the localized sheet has, with an empty name
+    pub empty: Vec<Untranslated>,
+}
+
+/// Coverage by language, then by sheet name
+pub type Coverage = BTreeMap<String, BTreeMap<&'static str, SheetCoverage>>;
+
+/// Looks up translations in one language, applying the missing translation policy and
+/// recording coverage
+struct Translator<'a> {
+    language: &'a str,
+    policy: MissingPolicy,
+    coverage: &'a mut BTreeMap<&'static str, SheetCoverage>,
+}
+
+impl<'a> Translator<'a> {
+    fn new(language: &'a str, policy: MissingPolicy, coverage: &'a mut Coverage) -> Translator<'a> {
+        Translator {
+            language,
+            policy,
+            coverage: coverage.entry(String::from(language)).or_default(),
+        }
+    }
+
+    /// The localized row's name, or the English name under the fallback policy. `None` means
+    /// the name is left out.
+    fn translate<T: Sheet>(
+        &mut self,
+        sheet: &SheetTable<T>,
+        id: u32,
+        english_name: &str,
+        name: impl Fn(&T) -> &str,
+    ) -> Result<Option<String>> {
+        let coverage = self.coverage.entry(T::NAME).or_default();
+        coverage.total += 1;
+
+        let untranslated = Untranslated {
+            id,
+            english_name: String::from(english_name),
+        };
+        match sheet.get(id).map(&name) {
+            Some(translation) if !translation.trim().is_empty() => {
+                coverage.translated += 1;
+                return Ok(Some(String::from(translation)));
             }
-            non_english_items.insert(item.id, item);
+            Some(_) => coverage.empty.push(untranslated),
+            None => coverage.missing.push(untranslated),
         }
 
-        for (id, english_item_name) in &english_items {
-            let non_english_item = non_english_items
-                .get(id)
-                .unwrap_or_else(|| panic!("item not found for {language}: {}", english_item_name));
-            translations.insert(
-                english_item_name.clone(),
-                clean_item_name(non_english_item.name.clone()),
-            );
+        match self.policy {
+            MissingPolicy::Strict => Err(Error::MissingTranslation {
+                file: sheet.path().to_path_buf(),
+                language: String::from(self.language),
+                id,
+                english_name: String::from(english_name),
+            }),
+            MissingPolicy::Fallback => Ok(Some(String::from(english_name))),
+            MissingPolicy::Omit => Ok(None),
         }
+    }
+}
 
-        write_json_file(
-            &translations,
-            format!("output/items_{language}.json").as_str(),
-        );
+/// Sorts names by id, so that output and coverage don't depend on hash order
+fn by_id(names: &HashMap<u32, String>) -> Vec<(u32, &String)> {
+    let mut names: Vec<_> = names.iter().map(|(id, name)| (*id, name)).collect();
+    names.sort();
+    names
+}
+
+/// An item's English name and its translations, which serializes as `{"en": ..., "ja": ...}`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ItemNames {
+    pub english: String,
+    /// Translated names, keyed by language
+    pub translations: BTreeMap<String, String>,
+}
+
+impl Serialize for ItemNames {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.translations.len() + 1))?;
+        map.serialize_entry("en", &self.english)?;
+        for (language, name) in &self.translations {
+            map.serialize_entry(language_tag(language), name)?;
+        }
+        map.end()
     }
 }
 
-fn clean_item_name(s: String) -> String {
-    s.replace("<SoftHyphen/>", "\u{00AD}")
+/// Item names in every language, keyed by item id
+pub type ItemTranslations = BTreeMap<u32, ItemNames>;
+
+pub fn build_translated_items(
+    data: &GameData,
+    english_items: &HashMap<u32, String>,
+    policy: MissingPolicy,
+    coverage: &mut Coverage,
+) -> Result<ItemTranslations> {
+    let mut items: ItemTranslations = english_items
+        .iter()
+        .map(|(id, english)| {
+            let names = ItemNames {
+                english: english.clone(),
+                translations: BTreeMap::new(),
+            };
+            (*id, names)
+        })
+        .collect();
+
+    for (language, localized) in data.languages() {
+        let mut translator = Translator::new(language, policy, coverage);
+
+        let non_english_items = localized.items()?;
+        for (id, names) in &mut items {
+            if let Some(translation) =
+                translator.translate(non_english_items, *id, &names.english, |item| &item.name)?
+            {
+                names
+                    .translations
+                    .insert(String::from(language), translation);
+            }
+        }
+    }
+
+    Ok(items)
+}
+
+/// English names that several items share while being translated differently. Files keyed
+/// by English name can only keep one of their translations.
+pub fn item_name_collisions(items: &ItemTranslations) -> Vec<(&str, Vec<u32>)> {
+    let mut ids_by_name: BTreeMap<&str, Vec<u32>> = BTreeMap::new();
+    for (id, names) in items {
+        ids_by_name.entry(&names.english).or_default().push(*id);
+    }
+
+    ids_by_name
+        .into_iter()
+        .filter(|(_, ids)| {
+            ids.iter()
+                .any(|id| items[id].translations != items[&ids[0]].translations)
+        })
+        .collect()
+}
+
+/// Translations keyed by English name, as in the legacy `items_{language tag}.json` files. Where
+/// items share an English name, the one with the highest id wins.
+pub fn legacy_item_translations(items: &ItemTranslations) -> Translations {
+    let mut translations_by_language = Translations::new();
+    for names in items.values() {
+        for (language, translation) in &names.translations {
+            translations_by_language
+                .entry(language.clone())
+                .or_default()
+                .insert(names.english.clone(), translation.clone());
+        }
+    }
+    translations_by_language
+}
+
+/// Writes `item_translations.json`
+pub fn write_item_translations(items: &ItemTranslations, output_dir: &Path) -> Result<()> {
+    write_json_file(items, output_dir.join("item_translations.json"))
 }
 
 pub fn build_translated_actions(
-    english_actions: HashMap<u32, String>,
-    english_craft_actions: HashMap<u32, String>,
-) {
-    for language in LANGUAGES {
+    data: &GameData,
+    english_actions: &HashMap<u32, String>,
+    english_craft_actions: &HashMap<u32, String>,
+    policy: MissingPolicy,
+    coverage: &mut Coverage,
+) -> Result<Translations> {
+    let mut translations_by_language = Translations::new();
+
+    for (language, localized) in data.languages() {
+        let mut translator = Translator::new(language, policy, coverage);
         let mut translations = BTreeMap::new();
 
-        let mut non_english_actions = HashMap::new();
-        for action in read_csv_data::<ActionRecord>(format!("data/{language}/Action.csv")) {
-            non_english_actions.insert(action.id, action.name);
+        let non_english_actions = localized.actions()?;
+        for (id, english_name) in by_id(english_actions) {
+            if let Some(translation) =
+                translator
+                    .translate(non_english_actions, id, english_name, |action| &action.name)?
+            {
+                translations.insert(english_name.clone(), translation);
+            }
         }
 
-        let mut non_english_craft_actions = HashMap::new();
-        for craft_action in
-            read_csv_data::<CraftActionRecord>(format!("data/{language}/CraftAction.csv"))
-        {
-            non_english_craft_actions.insert(craft_action.id, craft_action.name);
+        let non_english_craft_actions = localized.craft_actions()?;
+        for (id, english_name) in by_id(english_craft_actions) {
+            if let Some(translation) = translator.translate(
+                non_english_craft_actions,
+                id,
+                english_name,
+                |craft_action| &craft_action.name,
+            )? {
+                translations.insert(english_name.clone(), translation);
+            }
         }
 
-        for (id, english_name) in &english_actions {
-            let Some(non_english_name) = non_english_actions.get(id) else {
+        translations_by_language.insert(String::from(language), translations);
+    }
+
+    Ok(translations_by_language)
+}
+
+/// Translates status names, including the stack-suffixed names that status icons are
+/// published under
+pub fn build_translated_statuses(
+    data: &GameData,
+    english_statuses: &HashMap<u32, String>,
+    policy: MissingPolicy,
+    coverage: &mut Coverage,
+) -> Result<Translations> {
+    let mut translations_by_language = Translations::new();
+    let statuses = data.statuses()?;
+
+    for (language, localized) in data.languages() {
+        let mut translator = Translator::new(language, policy, coverage);
+        let mut translations = BTreeMap::new();
+
+        let non_english_statuses = localized.statuses()?;
+        // when names repeat, the newest status wins
+        for (id, english_name) in by_id(english_statuses) {
+            let Some(translation) =
+                translator.translate(non_english_statuses, id, english_name, |status| {
+                    &status.name
+                })?
+            else {
                 continue;
             };
-            translations.insert(english_name, non_english_name);
+            let max_stacks = statuses.get(id).map_or(0, |status| status.max_stacks);
+            translations.extend(
+                status_icon_names(english_name, max_stacks)
+                    .into_iter()
+                    .zip(status_icon_names(&translation, max_stacks)),
+            );
         }
 
-        for (id, english_name) in &english_craft_actions {
-            let Some(non_english_name) = non_english_craft_actions.get(id) else {
-                continue;
-            };
-            translations.insert(english_name, non_english_name);
+        translations_by_language.insert(String::from(language), translations);
+    }
+
+    Ok(translations_by_language)
+}
+
+/// Writes one `coverage_{language tag}.json` file per language, and prints a summary
+pub fn write_coverage(coverage: &Coverage, output_dir: &Path) -> Result<()> {
+    for (language, sheets) in coverage {
+        for (sheet, sheet_coverage) in sheets {
+            println!(
+                "{language}: {}/{} {sheet} name(s) translated, {} missing, {} empty",
+                sheet_coverage.translated,
+                sheet_coverage.total,
+                sheet_coverage.missing.len(),
+                sheet_coverage.empty.len()
+            );
         }
+        let tag = language_tag(language);
+        write_json_file(sheets, output_dir.join(format!("coverage_{tag}.json")))?;
+    }
 
+    Ok(())
+}
+
+/// Writes one `{prefix}_{language tag}.json` file per language
+pub fn write_translations(
+    translations: &Translations,
+    prefix: &str,
+    output_dir: &Path,
+) -> Result<()> {
+    for (language, translations) in translations {
+        let tag = language_tag(language);
         write_json_file(
-            &translations,
-            format!("output/actions_{language}.json").as_str(),
-        );
+            translations,
+            output_dir.join(format!("{prefix}_{tag}.json")),
+        )?;
     }
+
+    Ok(())
 }
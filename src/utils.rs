@@ -1,42 +1,136 @@
-use serde::Deserialize;
-use std::collections::hash_map::DefaultHasher;
+use serde::de;
 use std::fs::File;
-use std::hash::{Hash, Hasher};
-use std::io::prelude::Write;
+use std::io::{BufWriter, Write};
 use std::path::Path;
 
-pub fn read_csv_data<RecordType>(path: impl AsRef<Path>) -> impl Iterator<Item = RecordType>
+use crate::error::{self, Error};
+use crate::row::{Columns, RowDeserializer};
+
+pub fn read_csv_data<RecordType>(path: impl AsRef<Path>) -> error::Result<Vec<RecordType>>
 where
     RecordType: serde::de::DeserializeOwned,
 {
-    let reader = csv::Reader::from_path(path).unwrap();
-    reader.into_deserialize::<RecordType>().map(|r| r.unwrap())
+    let path = path.as_ref();
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_path(path)
+        .map_err(|e| csv_error(path, e))?;
+    let headers = read_headers(path, &mut reader)?;
+    let columns = Columns::new(&headers);
+
+    let mut records = vec![];
+    let mut row = csv::StringRecord::new();
+    while reader
+        .read_record(&mut row)
+        .map_err(|e| csv_error(path, e))?
+    {
+        let record = RecordType::deserialize(RowDeserializer::new(&columns, &row))
+            .map_err(|e| row_error(path, &row, &e))?;
+        records.push(record);
+    }
+
+    Ok(records)
 }
 
-pub fn bool_string<'de, D>(deserializer: D) -> Result<bool, D::Error>
-where
-    D: serde::de::Deserializer<'de>,
-{
-    let b = String::deserialize(deserializer)?;
-    match b.trim().to_lowercase().as_str() {
-        "true" => Ok(true),
-        "false" => Ok(false),
-        _ => Err(serde::de::Error::custom("invalid boolean string")),
+pub fn read_csv_headers(path: impl AsRef<Path>) -> error::Result<csv::StringRecord> {
+    let path = path.as_ref();
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_path(path)
+        .map_err(|e| csv_error(path, e))?;
+    read_headers(path, &mut reader)
+}
+
+/// Reads column names from either a plain CSV with a single header row, or a raw sheet
+/// export with three header rows: column indices (starting with `key`), column names,
+/// and column types.
+fn read_headers(path: &Path, reader: &mut csv::Reader<File>) -> error::Result<csv::StringRecord> {
+    let mut read_header_row = || -> error::Result<csv::StringRecord> {
+        let mut row = csv::StringRecord::new();
+        if !reader
+            .read_record(&mut row)
+            .map_err(|e| csv_error(path, e))?
+        {
+            return Err(Error::BadRow {
+                file: path.to_path_buf(),
+                line: None,
+                id: None,
+                message: String::from("missing header row"),
+            });
+        }
+        Ok(row)
+    };
+
+    let mut headers = read_header_row()?;
+    if first_field(&headers) == "key" {
+        headers = read_header_row()?;
+        // column types
+        read_header_row()?;
     }
+
+    // the first column is usually `#`, which may still carry a UTF-8 byte order mark
+    Ok(headers
+        .iter()
+        .enumerate()
+        .map(|(i, header)| {
+            if i == 0 {
+                first_field(&headers)
+            } else {
+                header
+            }
+        })
+        .collect())
+}
+
+fn first_field(row: &csv::StringRecord) -> &str {
+    row.get(0)
+        .unwrap_or_default()
+        .trim_start_matches('\u{feff}')
 }
 
-pub fn write_json_file<T>(data: &T, path: &str)
+fn csv_error(path: &Path, error: csv::Error) -> Error {
+    match error.kind() {
+        csv::ErrorKind::Io(_) => Error::io(path, error),
+        _ => Error::BadRow {
+            file: path.to_path_buf(),
+            line: error.position().map(csv::Position::line),
+            id: None,
+            message: error.to_string(),
+        },
+    }
+}
+
+fn row_error(path: &Path, row: &csv::StringRecord, error: &de::value::Error) -> Error {
+    let message = error.to_string();
+
+    // serde reports struct fields that have no matching column as missing fields
+    if let Some(column) = message
+        .strip_prefix("missing field `")
+        .and_then(|m| m.strip_suffix('`'))
+    {
+        return Error::MissingColumn {
+            file: path.to_path_buf(),
+            column: String::from(column),
+        };
+    }
+
+    Error::BadRow {
+        file: path.to_path_buf(),
+        // the same line numbers as the csv crate's own errors, so header rows count too
+        line: row.position().map(csv::Position::line),
+        id: row.get(0).map(String::from),
+        message,
+    }
+}
+
+pub fn write_json_file<T>(data: &T, path: impl AsRef<Path>) -> error::Result<()>
 where
     T: serde::Serialize,
 {
-    let json = serde_json::to_string(&data).unwrap();
-
-    let mut file = File::create(path).unwrap();
-    file.write_all(&json.into_bytes()).unwrap();
-}
+    let path = path.as_ref();
+    let file = File::create(path).map_err(|e| Error::io(path, e))?;
 
-pub fn calculate_hash<T: Hash>(t: &T) -> u64 {
-    let mut s = DefaultHasher::new();
-    t.hash(&mut s);
-    s.finish()
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer(&mut writer, data).map_err(|e| Error::io(path, e))?;
+    writer.flush().map_err(|e| Error::io(path, e))
 }
@@ -0,0 +1,187 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::error::Error;
+use crate::models::Sheet;
+use crate::row::{record_columns, Columns};
+use crate::utils::read_csv_headers;
+
+// columns that are at least this similar to a missing column are reported as renames
+const RENAME_SIMILARITY_THRESHOLD: f64 = 0.6;
+
+/// A sheet file, and the record type that reads it
+#[derive(Debug, Clone)]
+pub struct SheetSchema {
+    /// Path relative to the data directory, e.g. `jpn/Item.csv`
+    pub file: PathBuf,
+    /// Lists the columns the record type reads, given the sheet's columns. Indexed column
+    /// groups expand to as many columns as the sheet has.
+    columns: fn(&Columns) -> Vec<String>,
+}
+
+impl SheetSchema {
+    pub(crate) fn of<T: Sheet>() -> Self {
+        SheetSchema {
+            file: PathBuf::from(format!("{}.csv", T::NAME)),
+            columns: record_columns::<T>,
+        }
+    }
+
+    pub(crate) fn localized<T: Sheet>(language: &str) -> Self {
+        SheetSchema {
+            file: Path::new(language).join(format!("{}.csv", T::NAME)),
+            columns: record_columns::<T>,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rename {
+    pub expected: String,
+    pub found: String,
+}
+
+#[derive(Debug)]
+pub struct SheetReport {
+    pub file: PathBuf,
+    /// Expected columns that don't exist, and don't resemble any other column
+    pub missing: Vec<String>,
+    /// Expected columns that don't exist, paired with the most similar unused column
+    pub renamed: Vec<Rename>,
+    /// Named columns in the file that no record reads
+    pub unexpected: Vec<String>,
+    /// Set if the file's headers couldn't be read at all
+    pub unreadable: Option<Error>,
+}
+
+impl SheetReport {
+    pub fn is_valid(&self) -> bool {
+        self.missing.is_empty() && self.renamed.is_empty() && self.unreadable.is_none()
+    }
+}
+
+impl fmt::Display for SheetReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.file.display())?;
+
+        if let Some(error) = &self.unreadable {
+            return write!(f, "unreadable ({error})");
+        }
+
+        if self.is_valid() {
+            write!(f, "ok")?;
+        } else {
+            write!(f, "invalid")?;
+        }
+        for column in &self.missing {
+            write!(f, "\n  missing column `{column}`")?;
+        }
+        for Rename { expected, found } in &self.renamed {
+            write!(
+                f,
+                "\n  missing column `{expected}`, possibly renamed to `{found}`"
+            )?;
+        }
+        // the alternate format lists unused columns by name
+        if f.alternate() {
+            for column in &self.unexpected {
+                write!(f, "\n  unused column `{column}`")?;
+            }
+        } else if !self.unexpected.is_empty() {
+            write!(f, "\n  {} unused column(s)", self.unexpected.len())?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks each sheet's header against the columns its record type expects
+pub fn validate_sheets(data_dir: &Path, schemas: &[SheetSchema]) -> Vec<SheetReport> {
+    let mut checked = HashSet::new();
+    schemas
+        .iter()
+        .filter(|schema| checked.insert(&schema.file))
+        .map(|schema| validate_sheet(data_dir, schema))
+        .collect()
+}
+
+fn validate_sheet(data_dir: &Path, schema: &SheetSchema) -> SheetReport {
+    let mut report = SheetReport {
+        file: data_dir.join(&schema.file),
+        missing: vec![],
+        renamed: vec![],
+        unexpected: vec![],
+        unreadable: None,
+    };
+
+    let headers = match read_csv_headers(&report.file) {
+        Ok(headers) => headers,
+        Err(error) => {
+            report.unreadable = Some(error);
+            return report;
+        }
+    };
+
+    let expected = (schema.columns)(&Columns::new(&headers));
+
+    let mut missing: Vec<&str> = expected
+        .iter()
+        .map(String::as_str)
+        .filter(|column| !headers.iter().any(|header| header == *column))
+        .collect();
+
+    report.unexpected = headers
+        .iter()
+        .filter(|header| !header.is_empty() && !expected.iter().any(|column| column == header))
+        .map(String::from)
+        .collect();
+
+    // pair each missing column with the closest unused column, best matches first
+    let mut candidates = vec![];
+    for expected in &missing {
+        for found in &report.unexpected {
+            let score = similarity(expected, found);
+            if score >= RENAME_SIMILARITY_THRESHOLD {
+                candidates.push((score, *expected, found.clone()));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    for (_, expected, found) in candidates {
+        if missing.contains(&expected) && report.unexpected.contains(&found) {
+            missing.retain(|column| *column != expected);
+            report.unexpected.retain(|column| *column != found);
+            report.renamed.push(Rename {
+                expected: String::from(expected),
+                found,
+            });
+        }
+    }
+
+    report.missing = missing.into_iter().map(String::from).collect();
+    report
+}
+
+/// Normalized Levenshtein similarity, from 0 (nothing in common) to 1 (equal), ignoring case
+fn similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    let max_len = a.len().max(b.len());
+    if max_len == 0 {
+        return 1.0;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    1.0 - previous[b.len()] as f64 / max_len as f64
+}
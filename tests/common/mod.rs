@@ -0,0 +1,51 @@
+//! Data directories shared by the integration tests. Each test binary only uses some of
+//! these helpers.
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::Path;
+
+use tempfile::TempDir;
+
+/// ClassJob.csv with the crafting jobs, plus one combat class
+pub const CLASS_JOB: &str = "\
+#,Name,Abbreviation,ClassJobCategory,DohDolJobIndex
+1,gladiator,GLA,1,-1
+8,carpenter,CRP,33,0
+9,blacksmith,BSM,33,1
+10,armorer,ARM,33,2
+11,goldsmith,GSM,33,3
+12,leatherworker,LTW,33,4
+13,weaver,WVR,33,5
+14,alchemist,ALC,33,6
+15,culinarian,CUL,33,7
+";
+
+/// A data directory of its own for one test, which is removed when it's dropped
+pub struct DataDir(TempDir);
+
+impl DataDir {
+    pub fn new() -> Self {
+        DataDir(tempfile::tempdir().unwrap())
+    }
+
+    pub fn path(&self) -> &Path {
+        self.0.path()
+    }
+
+    /// Writes a file, given its path relative to the data directory, e.g. `jpn/Item.csv`
+    pub fn write(&self, file: &str, contents: &str) -> &Self {
+        let path = self.path().join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+        self
+    }
+
+    /// Copies a sheet from the repository's data directory
+    pub fn copy_sheet(&self, file: &str) -> &Self {
+        let source = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join(file);
+        self.write(file, &fs::read_to_string(source).unwrap())
+    }
+}
@@ -0,0 +1,37 @@
+//! Descriptions built from data/CraftAction.csv and data/Status.csv.
+
+mod common;
+
+use craftingway_data::craft_actions::build_craft_actions;
+use craftingway_data::descriptions::build_descriptions;
+use craftingway_data::game_data::GameData;
+use craftingway_data::markup::Format;
+
+use common::{DataDir, CLASS_JOB};
+
+#[test]
+fn describes_every_crafting_action() {
+    let dir = DataDir::new();
+    dir.copy_sheet("CraftAction.csv")
+        .copy_sheet("Status.csv")
+        .write("ClassJob.csv", CLASS_JOB)
+        .write(
+            "Action.csv",
+            "#,Name,Icon,ActionCategory,ClassJob,ClassJobLevel,PrimaryCostValue,IsPlayerAction\n",
+        );
+    let data = GameData::new(dir.path());
+
+    let descriptions = build_descriptions(&data, Format::Plain).unwrap().english;
+    for craft_action in build_craft_actions(&data).unwrap() {
+        assert!(
+            descriptions.actions.contains_key(&craft_action.name),
+            "{} has no description",
+            craft_action.name
+        );
+    }
+
+    // Daring Touch's rows have a ClassJob of 0
+    let daring_touch = &descriptions.actions["Daring Touch"];
+    assert_eq!(daring_touch[0].numbers["Efficiency"], 150);
+    assert_eq!(daring_touch[0].numbers["Success Rate"], 60);
+}
@@ -0,0 +1,160 @@
+//! Base progress and quality for recipe levels read from data/RecipeLevelTable.csv.
+//!
+//! Each case names the crafter's stats, the recipe level and where its expected values come
+//! from. So far every case is [`Source::Formula`]: its values are worked out by hand from
+//! `craftsmanship * 10 / progress_div + 2` and `control * 10 / quality_div + 35`, flooring
+//! after each step, then the recipe's modifiers when the crafter isn't above the recipe's job
+//! level. They only catch the code drifting from that formula, not the formula being wrong;
+//! that takes [`Source::InGame`] cases.
+
+use craftingway_data::formulas::{apply_percent, base_values, BaseValues, RecipeParams};
+use craftingway_data::game_data::GameData;
+
+#[derive(Debug)]
+enum Source {
+    /// Worked out by hand from the formula
+    Formula,
+    /// Observed in game, as the progress of Basic Synthesis and the quality of Basic Touch
+    /// (both 100% efficiency) in Normal condition without buffs, with the crafter's stats as
+    /// shown on the character sheet
+    #[allow(dead_code)] // until the first observation is added
+    InGame { patch: &'static str },
+}
+
+struct Case {
+    /// RecipeLevelTable row
+    rlvl: u32,
+    crafter_level: u32,
+    craftsmanship: u32,
+    control: u32,
+    expected: BaseValues,
+    source: Source,
+}
+
+const CASES: &[Case] = &[
+    // level 1 recipe with 100% modifiers, at its suggested craftsmanship
+    // 220 / 50 = 4, + 2 = 6; 200 / 30 = 6, + 35 = 41
+    Case {
+        rlvl: 1,
+        crafter_level: 1,
+        craftsmanship: 22,
+        control: 20,
+        expected: BaseValues {
+            progress: 6,
+            quality: 41,
+        },
+        source: Source::Formula,
+    },
+    // level 90 recipe, 90% / 80% modifiers
+    // 40410 / 130 = 310, + 2 = 312, * 90% = 280; 39870 / 115 = 346, + 35 = 381, * 80% = 304
+    Case {
+        rlvl: 560,
+        crafter_level: 90,
+        craftsmanship: 4041,
+        control: 3987,
+        expected: BaseValues {
+            progress: 280,
+            quality: 304,
+        },
+        source: Source::Formula,
+    },
+    // level 90 4-star recipe, 80% / 70% modifiers
+    // 312 * 80% = 249; 381 * 70% = 266
+    Case {
+        rlvl: 640,
+        crafter_level: 90,
+        craftsmanship: 4041,
+        control: 3987,
+        expected: BaseValues {
+            progress: 249,
+            quality: 266,
+        },
+        source: Source::Formula,
+    },
+    // level 100 recipe, 90% / 75% modifiers
+    // 40410 / 170 = 237, + 2 = 239, * 90% = 215; 39870 / 150 = 265, + 35 = 300, * 75% = 225
+    Case {
+        rlvl: 690,
+        crafter_level: 100,
+        craftsmanship: 4041,
+        control: 3987,
+        expected: BaseValues {
+            progress: 215,
+            quality: 225,
+        },
+        source: Source::Formula,
+    },
+    // a level 100 crafter on a level 90 recipe skips its 90% / 80% modifiers
+    Case {
+        rlvl: 560,
+        crafter_level: 100,
+        craftsmanship: 4041,
+        control: 3987,
+        expected: BaseValues {
+            progress: 312,
+            quality: 381,
+        },
+        source: Source::Formula,
+    },
+    // a level 90 crafter on a level 80 3-star recipe skips its 80% / 70% modifiers
+    // 40410 / 110 = 367, + 2 = 369; 39870 / 90 = 443, + 35 = 478
+    Case {
+        rlvl: 480,
+        crafter_level: 90,
+        craftsmanship: 4041,
+        control: 3987,
+        expected: BaseValues {
+            progress: 369,
+            quality: 478,
+        },
+        source: Source::Formula,
+    },
+];
+
+#[test]
+fn base_values_by_recipe_level() {
+    let data = GameData::new(concat!(env!("CARGO_MANIFEST_DIR"), "/data"));
+    let recipe_levels = data.recipe_levels().unwrap();
+
+    for case in CASES {
+        let recipe_level = recipe_levels.get(case.rlvl).unwrap();
+        assert_eq!(
+            base_values(
+                recipe_level,
+                case.crafter_level,
+                case.craftsmanship,
+                case.control
+            ),
+            case.expected,
+            "rlvl {} at level {} with {} craftsmanship and {} control ({:?})",
+            case.rlvl,
+            case.crafter_level,
+            case.craftsmanship,
+            case.control,
+            case.source,
+        );
+    }
+}
+
+#[test]
+fn recipe_params_from_data() {
+    let data = GameData::new(concat!(env!("CARGO_MANIFEST_DIR"), "/data"));
+    let level_640 = data.recipe_levels().unwrap().get(640).unwrap();
+    assert_eq!(
+        RecipeParams::from(level_640),
+        RecipeParams {
+            job_level: 90,
+            progress_div: 130,
+            progress_mod: 80,
+            quality_div: 115,
+            quality_mod: 70,
+        }
+    );
+}
+
+#[test]
+fn efficiency_rounds_down() {
+    // Basic Synthesis at level 90 has 120% efficiency: 249 * 1.2 = 298.8
+    assert_eq!(apply_percent(249, 120), 298);
+    assert_eq!(apply_percent(266, 100), 266);
+}
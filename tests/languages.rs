@@ -0,0 +1,36 @@
+//! Language detection from the subdirectories of a data directory.
+
+mod common;
+
+use craftingway_data::translated_items::{detect_languages, SkippedDir, LOCALIZED_SHEETS};
+
+use common::DataDir;
+
+#[test]
+fn detects_subdirectories_with_localized_sheets() {
+    let dir = DataDir::new();
+    // a language that isn't in LANGUAGES is still used, after the known ones
+    for language in ["tha", "jpn", "deu"] {
+        for sheet in LOCALIZED_SHEETS {
+            dir.write(&format!("{language}/{sheet}.csv"), "#\n");
+        }
+    }
+    dir.write("raw/Item.csv", "#\n").write("Item.csv", "#\n");
+    // ClassJob.csv is optional, since job names can stay in English
+    dir.write("jpn/ClassJob.csv", "#\n");
+
+    let detected = detect_languages(dir.path()).unwrap();
+
+    assert_eq!(detected.languages, ["jpn", "deu", "tha"]);
+    assert_eq!(
+        detected.skipped,
+        [SkippedDir {
+            name: String::from("raw"),
+            missing: vec![
+                String::from("Action.csv"),
+                String::from("CraftAction.csv"),
+                String::from("Status.csv"),
+            ],
+        }]
+    );
+}
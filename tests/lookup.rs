@@ -0,0 +1,54 @@
+//! Lookups of localized names, built from a small data directory.
+
+mod common;
+
+use craftingway_data::game_data::GameData;
+use craftingway_data::lookup::{build_lookups, LookupKind};
+use craftingway_data::translated_items::{build_translated_items, Coverage, MissingPolicy};
+
+use common::{DataDir, CLASS_JOB};
+
+const ACTION: &str =
+    "#,Name,Icon,ActionCategory,ClassJob,ClassJobLevel,PrimaryCostValue,IsPlayerAction\n";
+
+const ITEM_HEADER: &str = "#,Name,Level{Item},Level{Equip},ItemAction,CanBeHq,EquipSlotCategory";
+
+#[test]
+fn only_looks_up_real_translations() {
+    let dir = DataDir::new();
+    for language in ["", "jpn/"] {
+        dir.copy_sheet(&format!("{language}CraftAction.csv"))
+            .copy_sheet(&format!("{language}Status.csv"))
+            .write(&format!("{language}Action.csv"), ACTION);
+    }
+    // Tin Ore has no Japanese name
+    dir.write("ClassJob.csv", CLASS_JOB)
+        .write(
+            "Item.csv",
+            &format!("{ITEM_HEADER}\n5106,Copper Ore,1,1,0,False,0\n5107,Tin Ore,1,1,0,False,0\n"),
+        )
+        .write(
+            "jpn/Item.csv",
+            &format!("{ITEM_HEADER}\n5106,銅鉱,1,1,0,False,0\n5107,,1,1,0,False,0\n"),
+        );
+
+    let data = GameData::new(dir.path()).with_languages(["jpn"]);
+    let english_items = [5106, 5107]
+        .into_iter()
+        .map(|id| (id, data.items().unwrap().get(id).unwrap().name.clone()))
+        .collect();
+    let items = build_translated_items(
+        &data,
+        &english_items,
+        MissingPolicy::Fallback,
+        &mut Coverage::new(),
+    )
+    .unwrap();
+    let lookups = build_lookups(&data, &items).unwrap();
+
+    // the item translations fall back to English, but the lookup doesn't
+    assert_eq!(items[&5107].translations["jpn"], "Tin Ore");
+    let lookup = &lookups["jpn"];
+    assert_eq!(lookup["銅鉱"][0].kind, LookupKind::Item);
+    assert!(!lookup.contains_key("tin ore"));
+}
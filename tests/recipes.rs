@@ -0,0 +1,186 @@
+//! Recipes built from a small hand-written data directory.
+
+mod common;
+
+use craftingway_data::game_data::GameData;
+use craftingway_data::recipes::{build_localized_recipes, build_recipes};
+use craftingway_data::translated_items::{ItemNames, ItemTranslations};
+
+use common::{DataDir, CLASS_JOB};
+
+const ITEM: &str = "\
+#,Name,Level{Item},Level{Equip},ItemAction,CanBeHq,EquipSlotCategory
+5056,Bronze Ingot,5,1,0,True,0
+5106,Copper Ore,1,1,0,False,0
+5107,Tin Ore,1,1,0,False,0
+";
+
+const RECIPE_LEVEL_TABLE: &str = "\
+#,ClassJobLevel,Stars,Durability,Difficulty,Quality,ProgressDivider,QualityDivider,ProgressModifier,QualityModifier,ConditionsFlag
+1,1,0,60,20,80,50,30,100,100,15
+";
+
+const RECIPE_HEADER: &str = "#,CraftType,RecipeLevelTable,Item{Result},Amount{Result},DifficultyFactor,QualityFactor,DurabilityFactor,RequiredQuality,RequiredCraftsmanship,RequiredControl,QuickSynthCraftsmanship,QuickSynthControl,SecretRecipeBook,Quest,CanQuickSynth,CanHq,ExpRewarded,Status{Required},Item{Required},IsSpecializationRequired,IsExpert,MaterialQualityFactor,PatchNumber,Item{Ingredient}[0],Amount{Ingredient}[0],Item{Ingredient}[1],Amount{Ingredient}[1]";
+
+/// A Bronze Ingot recipe for the job at `craft_type`
+fn recipe_row(id: u32, craft_type: u32, patch_number: u32, required_control: u32) -> String {
+    format!(
+        "{id},{craft_type},1,5056,1,100,100,100,0,0,{required_control},0,0,0,0,True,True,True,0,0,False,False,50,{patch_number},5106,2,5107,1"
+    )
+}
+
+/// A data directory with the given jobs and recipes, and a RecipeLookup.csv that lists
+/// recipes for Bronze Ingot
+fn data_dir(class_job: &str, recipes: &[String], lookup: &str) -> DataDir {
+    let dir = DataDir::new();
+    dir.write("ClassJob.csv", class_job)
+        .write("Item.csv", ITEM)
+        .write("RecipeLevelTable.csv", RECIPE_LEVEL_TABLE)
+        .write(
+            "Recipe.csv",
+            &format!("{RECIPE_HEADER}\n{}\n", recipes.join("\n")),
+        )
+        .write("RecipeLookup.csv", lookup);
+    dir
+}
+
+/// RecipeLookup.csv with one column per job, giving each job's Bronze Ingot recipe
+fn lookup(jobs: &str, recipe_ids: &str) -> String {
+    format!("#,{jobs}\n5056,{recipe_ids}\n")
+}
+
+const JOBS: &str = "CRP,BSM,ARM,GSM,LTW,WVR,ALC,CUL";
+
+#[test]
+fn merges_recipes_that_differ_only_in_bookkeeping() {
+    // the blacksmith and armorer recipes were added in different patches
+    let dir = data_dir(
+        CLASS_JOB,
+        &[recipe_row(1, 1, 200, 0), recipe_row(2, 2, 310, 0)],
+        &lookup(JOBS, "0,1,2,0,0,0,0,0"),
+    );
+    let recipes = build_recipes(&GameData::new(dir.path())).unwrap();
+
+    assert_eq!(recipes.recipes.len(), 1);
+    let recipe = &recipes.recipes[0];
+    assert_eq!(recipe.recipe_ids, [1, 2]);
+    assert_eq!(recipe.jobs, [9, 10]);
+    assert_eq!(recipe.patch_number, 200);
+    assert_eq!(recipes.report.merges.len(), 1);
+    assert!(recipes.report.near_collisions.is_empty());
+}
+
+#[test]
+fn keeps_recipes_apart_that_differ_in_crafting() {
+    let dir = data_dir(
+        CLASS_JOB,
+        &[recipe_row(1, 1, 200, 0), recipe_row(2, 2, 200, 500)],
+        &lookup(JOBS, "0,1,2,0,0,0,0,0"),
+    );
+    let recipes = build_recipes(&GameData::new(dir.path())).unwrap();
+
+    assert_eq!(recipes.recipes.len(), 2);
+    assert!(recipes.report.merges.is_empty());
+    assert_eq!(
+        recipes.report.near_collisions[0].fields,
+        ["required_control"]
+    );
+}
+
+#[test]
+fn reads_a_column_for_every_crafting_job() {
+    // a ninth crafter, with its own RecipeLookup column
+    let class_job = format!("{CLASS_JOB}42,tinkerer,TNK,33,8\n");
+    let dir = data_dir(
+        &class_job,
+        &[recipe_row(1, 1, 200, 0), recipe_row(2, 8, 200, 500)],
+        &lookup(&format!("{JOBS},TNK"), "0,1,0,0,0,0,0,0,2"),
+    );
+    let recipes = build_recipes(&GameData::new(dir.path()));
+
+    // an export from before the job was added doesn't have its column
+    dir.write("RecipeLookup.csv", &lookup(JOBS, "0,1,0,0,0,0,0,0"));
+    let error = build_recipes(&GameData::new(dir.path())).err().unwrap();
+
+    let jobs: Vec<_> = recipes
+        .unwrap()
+        .recipes
+        .iter()
+        .map(|recipe| recipe.jobs.clone())
+        .collect();
+    assert_eq!(jobs, [vec![9], vec![42]]);
+    assert!(error.to_string().ends_with("missing column `TNK`"));
+}
+
+#[test]
+fn localizes_job_names() {
+    let dir = data_dir(
+        CLASS_JOB,
+        &[recipe_row(1, 1, 200, 0), recipe_row(2, 2, 200, 0)],
+        &lookup(JOBS, "0,1,2,0,0,0,0,0"),
+    );
+    // the armorer has no Japanese name, so it stays in English
+    dir.write(
+        "jpn/ClassJob.csv",
+        &CLASS_JOB
+            .replace("blacksmith", "鍛冶師")
+            .replace("armorer", ""),
+    );
+
+    let data = GameData::new(dir.path()).with_languages(["jpn"]);
+    let recipes = build_recipes(&data).unwrap();
+    let items = ItemTranslations::from([(
+        5056,
+        ItemNames {
+            english: String::from("Bronze Ingot"),
+            translations: [(String::from("jpn"), String::from("ブロンズインゴット"))].into(),
+        },
+    )]);
+    let localized = build_localized_recipes(&data, &recipes.recipes, &items).unwrap();
+
+    assert_eq!(recipes.recipes[0].job_names, ["blacksmith", "armorer"]);
+    let recipe = &localized["ja"][0];
+    assert_eq!(recipe.name, "ブロンズインゴット");
+    assert_eq!(recipe.job_names, ["鍛冶師", "armorer"]);
+}
+
+#[test]
+fn keeps_english_job_names_without_a_localized_class_job_sheet() {
+    let dir = data_dir(
+        CLASS_JOB,
+        &[recipe_row(1, 1, 200, 0)],
+        &lookup(JOBS, "0,1,0,0,0,0,0,0"),
+    );
+
+    let data = GameData::new(dir.path()).with_languages(["jpn"]);
+    let recipes = build_recipes(&data).unwrap();
+    let localized =
+        build_localized_recipes(&data, &recipes.recipes, &ItemTranslations::new()).unwrap();
+
+    assert_eq!(localized["ja"][0].job_names, ["blacksmith"]);
+}
+
+#[test]
+fn reads_localized_job_columns_by_english_abbreviation() {
+    let dir = data_dir(
+        CLASS_JOB,
+        &[recipe_row(1, 1, 200, 0)],
+        &lookup(JOBS, "0,1,0,0,0,0,0,0"),
+    );
+    // the Japanese sheet abbreviates the blacksmith as 鍛, but its RecipeLookup column is
+    // still named BSM
+    dir.write("jpn/ClassJob.csv", &CLASS_JOB.replace("BSM", "鍛"))
+        .write("jpn/RecipeLookup.csv", &lookup(JOBS, "0,1,0,0,0,0,0,0"));
+
+    let data = GameData::new(dir.path()).with_languages(["jpn"]);
+    let (_, localized) = data.languages().next().unwrap();
+    let lookup = localized.recipe_lookups().unwrap().get(5056).unwrap();
+    let recipe_ids: Vec<_> = lookup
+        .recipe_ids(localized)
+        .unwrap()
+        .into_iter()
+        .map(|(job, id)| (job.abbreviation.as_str(), id))
+        .collect();
+
+    assert_eq!(recipe_ids, [("BSM", 1)]);
+}
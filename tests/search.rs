@@ -0,0 +1,70 @@
+//! Queries against a small hand-built search index.
+
+use std::collections::BTreeMap;
+
+use craftingway_data::search::{build_search_index, MatchRank, SearchIndex};
+use craftingway_data::translated_items::{ItemNames, ItemTranslations};
+
+fn item(english: &str, translations: &[(&str, &str)]) -> ItemNames {
+    ItemNames {
+        english: String::from(english),
+        translations: translations
+            .iter()
+            .map(|(language, name)| (String::from(*language), String::from(*name)))
+            .collect(),
+    }
+}
+
+fn index() -> SearchIndex {
+    let items: ItemTranslations = BTreeMap::from([
+        (
+            4551,
+            item(
+                "Hi-Potion",
+                &[("jpn", "ハイポーション"), ("deu", "Hi-Trank")],
+            ),
+        ),
+        (
+            4552,
+            item("Potion", &[("jpn", "ポーション"), ("deu", "Trank")]),
+        ),
+        (
+            5057,
+            item(
+                "Iron Ingot",
+                &[("jpn", "アイアンインゴット"), ("deu", "Eisenbarren")],
+            ),
+        ),
+    ]);
+    build_search_index(&[], &items)
+}
+
+fn ids(index: &SearchIndex, query: &str) -> Vec<u32> {
+    index
+        .search(query, 10)
+        .iter()
+        .map(|hit| hit.item.id)
+        .collect()
+}
+
+#[test]
+fn ranks_exact_then_prefix_then_substring() {
+    let index = index();
+    assert_eq!(ids(&index, "potion"), [4552, 4551]);
+    assert_eq!(ids(&index, "trank"), [4552, 4551]);
+    assert_eq!(ids(&index, "ingot"), [5057]);
+    assert_eq!(index.search("ingot", 10)[0].rank, MatchRank::WordPrefix);
+    assert!(ids(&index, "mythril").is_empty());
+}
+
+#[test]
+fn normalizes_queries() {
+    let index = index();
+    // half-width katakana and full-width letters
+    assert_eq!(ids(&index, "ﾎﾟｰｼｮﾝ"), [4552, 4551]);
+    assert_eq!(ids(&index, "ＥＩＳＥＮ"), [5057]);
+
+    let hit = &index.search("ハイ", 10)[0];
+    assert_eq!((hit.item.id, hit.language), (4551, "ja"));
+    assert_eq!(hit.rank, MatchRank::Prefix);
+}
@@ -0,0 +1,271 @@
+mod common;
+
+use std::fs;
+use std::sync::{Arc, OnceLock};
+
+use craftingway_data::conditions::{Condition, Conditions};
+use craftingway_data::craft_actions::{
+    build_craft_actions, write_craft_actions, CraftActionOutput,
+};
+use craftingway_data::formulas::BaseValues;
+use craftingway_data::game_data::GameData;
+use craftingway_data::simulator::{
+    Action, ActionError, ActionParams, ActionTable, Buff, CraftSettings, CraftState, CrafterStats,
+    Potency, Status,
+};
+
+use common::{DataDir, CLASS_JOB};
+
+/// The crafting buffs in Action.csv: name, carpenter's id, level and CP cost. The other jobs'
+/// ids follow the carpenter's, in game order.
+const BUFFS: [(&str, u32, u32, u32); 7] = [
+    ("Waste Not", 4631, 15, 56),
+    ("Veneration", 19297, 15, 18),
+    ("Great Strides", 260, 21, 32),
+    ("Innovation", 19004, 26, 18),
+    ("Final Appraisal", 19012, 42, 1),
+    ("Waste Not II", 4639, 47, 98),
+    ("Manipulation", 4574, 65, 96),
+];
+
+/// Action.csv with a row per job for each buff, plus a combat action
+fn action_csv() -> String {
+    let mut csv = String::from(
+        "#,Name,Icon,ActionCategory,ClassJob,ClassJobLevel,PrimaryCostValue,IsPlayerAction\n\
+         9,Fast Blade,158,3,1,1,0,True\n",
+    );
+    for (name, first_id, level, cost) in BUFFS {
+        for job in 0..8 {
+            let id = first_id + job;
+            let class_job = 8 + job;
+            csv += &format!("{id},{name},1,7,{class_job},{level},{cost},True\n");
+        }
+    }
+    csv
+}
+
+/// actions_data.json as written from data/CraftAction.csv and the buffs
+fn actions_data() -> &'static str {
+    static ACTIONS_DATA: OnceLock<String> = OnceLock::new();
+    ACTIONS_DATA.get_or_init(|| {
+        let dir = DataDir::new();
+        dir.copy_sheet("CraftAction.csv")
+            .write("ClassJob.csv", CLASS_JOB)
+            .write("Action.csv", &action_csv());
+
+        let craft_actions = build_craft_actions(&GameData::new(dir.path())).unwrap();
+        write_craft_actions(&craft_actions, dir.path()).unwrap();
+        fs::read_to_string(dir.path().join("actions_data.json")).unwrap()
+    })
+}
+
+fn action_table() -> Arc<ActionTable> {
+    let craft_actions: Vec<CraftActionOutput> = serde_json::from_str(actions_data()).unwrap();
+    Arc::new(ActionTable::new(&craft_actions))
+}
+
+/// A level 90 recipe with only the Normal condition, so quality doesn't depend on the seed
+fn settings() -> CraftSettings {
+    CraftSettings {
+        crafter: CrafterStats {
+            level: 90,
+            craftsmanship: 4041,
+            control: 3987,
+            cp: 600,
+            is_specialist: false,
+        },
+        job_level: 90,
+        max_progress: 4400,
+        max_quality: 9000,
+        max_durability: 70,
+        is_expert: false,
+        conditions: Conditions::from_flag(1),
+        base: BaseValues {
+            progress: 249,
+            quality: 266,
+        },
+        actions: action_table(),
+    }
+}
+
+fn run(seed: u64, actions: &[Action]) -> CraftState {
+    actions
+        .iter()
+        .fold(CraftState::new(settings(), seed), |state, &action| {
+            state.apply(action).unwrap()
+        })
+}
+
+#[test]
+fn progress_and_buffs() {
+    let state = run(0, &[Action::Veneration, Action::BasicSynthesis]);
+    // 249 * 120% * 150% = 448.2
+    assert_eq!(state.progress, 448);
+    assert_eq!(state.durability, 60);
+    assert_eq!(state.cp, 600 - 18);
+    assert_eq!(state.buff(Buff::Veneration).unwrap().duration, Some(3));
+}
+
+#[test]
+fn inner_quiet_and_combos() {
+    let state = run(0, &[Action::BasicTouch]);
+    assert_eq!(state.quality, 266);
+    assert_eq!(state.inner_quiet(), 1);
+    assert_eq!(state.cp_cost(Action::StandardTouch), Some(18));
+
+    // 266 * 125% * 110% = 365.75
+    let state = state.apply(Action::StandardTouch).unwrap();
+    assert_eq!(state.quality, 266 + 365);
+    assert_eq!(state.inner_quiet(), 2);
+}
+
+#[test]
+fn invalid_actions() {
+    let state = CraftState::new(settings(), 0);
+    assert!(matches!(
+        state.apply(Action::ByregotsBlessing),
+        Err(ActionError::Unusable { .. })
+    ));
+    assert!(matches!(
+        state.apply(Action::QuickInnovation),
+        Err(ActionError::LevelTooLow { level: 96, .. })
+    ));
+
+    let state = state.apply(Action::Observe).unwrap();
+    assert!(matches!(
+        state.apply(Action::MuscleMemory),
+        Err(ActionError::Unusable { .. })
+    ));
+}
+
+#[test]
+fn finished_crafts() {
+    let actions = [Action::Veneration, Action::Groundwork, Action::Groundwork];
+    let mut state = run(0, &actions);
+    while state.status() == Status::InProgress {
+        state = state.apply(Action::Groundwork).unwrap();
+    }
+    assert_eq!(state.status(), Status::Completed);
+    assert_eq!(
+        state.apply(Action::BasicSynthesis),
+        Err(ActionError::Finished)
+    );
+}
+
+#[test]
+fn seeded_runs_repeat() {
+    let actions = [Action::HastyTouch; 6];
+    assert_eq!(run(7, &actions), run(7, &actions));
+
+    let conditions = |seed| {
+        let settings = CraftSettings {
+            conditions: Conditions::from_flag(15),
+            ..settings()
+        };
+        let mut state = CraftState::new(settings, seed);
+        let mut conditions = vec![];
+        for _ in 0..6 {
+            state = state.apply(Action::Observe).unwrap();
+            conditions.push(state.condition);
+        }
+        conditions
+    };
+    assert_eq!(conditions(1), conditions(1));
+    assert!((0..20).any(|seed| conditions(seed).contains(&Condition::Good)));
+}
+
+#[test]
+fn careful_observation() {
+    let specialist = CraftSettings {
+        crafter: CrafterStats {
+            is_specialist: true,
+            ..settings().crafter
+        },
+        conditions: Conditions::from_flag(15),
+        ..settings()
+    };
+    assert!(matches!(
+        CraftState::new(settings(), 0).apply(Action::CarefulObservation),
+        Err(ActionError::Unusable { .. })
+    ));
+
+    let mut state = CraftState::new(specialist, 3);
+    for _ in 0..3 {
+        state = state.apply(Action::CarefulObservation).unwrap();
+    }
+    // the condition changes, but no step is taken
+    assert_eq!((state.step, state.cp), (0, 600));
+    assert!(matches!(
+        state.apply(Action::CarefulObservation),
+        Err(ActionError::Unusable { .. })
+    ));
+}
+
+#[test]
+fn actions_match_the_action_data() {
+    let table = action_table();
+    let json: serde_json::Value = serde_json::from_str(actions_data()).unwrap();
+
+    for entry in json.as_array().unwrap() {
+        let name = entry["name"].as_str().unwrap();
+        let action = Action::from_name(name)
+            .unwrap_or_else(|| panic!("the simulator doesn't have {name:?}"));
+        assert_eq!(
+            table.get(action),
+            Some(ActionParams {
+                level: entry["level"].as_u64().unwrap() as u32,
+                cp_cost: entry["cp_cost"].as_u64().unwrap() as u32,
+                is_specialist: entry["is_specialist"].as_bool().unwrap(),
+            }),
+            "{name}"
+        );
+    }
+
+    for action in Action::ALL {
+        assert!(table.get(action).is_some(), "{action} has no action data");
+    }
+    let veneration = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|entry| entry["name"] == "Veneration")
+        .unwrap();
+    assert_eq!(veneration["level"], 15);
+    assert_eq!(veneration["cp_cost"], 18);
+    assert_eq!(veneration["ids"]["8"], 19297);
+    assert_eq!(veneration["ids"]["15"], 19304);
+    assert_eq!(
+        table.get(Action::CarefulObservation),
+        Some(ActionParams {
+            level: 55,
+            cp_cost: 0,
+            is_specialist: true,
+        })
+    );
+}
+
+#[test]
+fn potency_from_descriptions() {
+    let table = action_table();
+    let potency = |progress, quality, success_rate| Potency {
+        progress,
+        quality,
+        success_rate,
+    };
+
+    // Basic Synthesis gets a trait at level 31
+    assert_eq!(
+        table.potency(Action::BasicSynthesis, 30),
+        potency(100, 0, 100)
+    );
+    assert_eq!(
+        table.potency(Action::BasicSynthesis, 31),
+        potency(120, 0, 100)
+    );
+    assert_eq!(table.potency(Action::HastyTouch, 90), potency(0, 100, 60));
+    assert_eq!(
+        table.potency(Action::DelicateSynthesis, 100),
+        potency(150, 100, 100)
+    );
+    assert_eq!(table.potency(Action::Veneration, 90), potency(0, 0, 100));
+}